@@ -14,7 +14,7 @@ fn main() -> PolarsResult<()> {
 
   plot.bar_chart(df.column("label")?, df.column("value")?);
 
-  plot.show();
+  plot.show()?;
 
   Ok(())
 }
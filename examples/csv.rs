@@ -15,7 +15,7 @@ fn main() -> PolarsResult<()> {
   let filtered = df.filter(col("a").gt_eq(lit(2))).collect()?;
   plot.line(filtered.column("a")?, filtered.column("b")?);
 
-  plot.show();
+  plot.show()?;
 
   Ok(())
 }
@@ -12,7 +12,7 @@ fn main() -> PolarsResult<()> {
 
   plot.histogram(df.column("rand")?, 30);
 
-  plot.show();
+  plot.show()?;
 
   Ok(())
 }
@@ -12,7 +12,7 @@ fn main() -> PolarsResult<()> {
 
   plot.scatter(df.column("x")?, df.column("y")?).trendline(TrendlineKind::LINEAR);
 
-  plot.show();
+  plot.show()?;
 
   Ok(())
 }
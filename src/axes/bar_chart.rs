@@ -3,50 +3,120 @@ use polars::prelude::*;
 
 use crate::{
   Range, ResultExt,
-  bounds::{DataBounds, DataRange, RangeUnit, ViewportTransform},
+  axes::AxisBinding,
+  bounds::{self, DataBounds, DataRange, RangeUnit, ViewportTransform},
   render::Render,
 };
 
 pub struct BarChartAxes<'a> {
   labels: &'a Column,
   values: &'a Column,
+  label:  Option<String>,
+
+  horizontal:         bool,
+  pub(crate) binding: AxisBinding,
 }
 
 impl<'a> BarChartAxes<'a> {
   pub(crate) fn new(labels: &'a Column, values: &'a Column) -> Self {
-    BarChartAxes { labels, values }
+    BarChartAxes { labels, values, label: None, horizontal: false, binding: AxisBinding::default() }
+  }
+
+  /// Puts the categories on the y axis, with bars running to the right. See
+  /// [`Plot::barh`](crate::Plot::barh).
+  pub fn horizontal(&mut self) -> &mut Self {
+    self.horizontal = true;
+    self
+  }
+
+  /// Draws this series against the plot's secondary x axis. See
+  /// [`Plot::x2`](crate::Plot::x2).
+  pub fn on_x2(&mut self) -> &mut Self {
+    self.binding.x2 = true;
+    self
+  }
+
+  /// Draws this series against the plot's secondary y axis. See
+  /// [`Plot::y2`](crate::Plot::y2).
+  pub fn on_y2(&mut self) -> &mut Self {
+    self.binding.y2 = true;
+    self
+  }
+
+  /// Names this series in the legend. Defaults to the name of the values
+  /// column.
+  pub fn label(&mut self, label: &str) -> &mut Self {
+    self.label = Some(label.to_string());
+    self
+  }
+
+  pub(crate) fn legend_label(&self) -> String {
+    self.label.clone().unwrap_or_else(|| self.values.name().to_string())
+  }
+
+  pub(crate) fn describe(&self) -> String {
+    format!("bar chart of `{}` against `{}`", self.values.name(), self.labels.name())
   }
 
   pub(crate) fn data_bounds(&self) -> PolarsResult<DataBounds<'_>> {
-    Ok(DataBounds {
-      x: DataRange::Categorical(self.labels),
-      y: DataRange::Continuous {
-        range:      Range::new(
-          0.0,
-          self.values.max_reduce()?.into_value().try_extract::<i64>()? as f64,
-        )
-        .into(),
-        unit:       RangeUnit::Absolute,
-        margin_min: false,
-        margin_max: true,
-      },
+    let categories = DataRange::categorical(self.labels)?;
+    let values = DataRange::Continuous {
+      range:      Range::new(
+        0.0,
+        self.values.max_reduce()?.into_value().try_extract::<i64>()? as f64,
+      )
+      .into(),
+      unit:       RangeUnit::Absolute,
+      margin_min: false,
+      margin_max: true,
+    };
+
+    Ok(if self.horizontal {
+      DataBounds { x: values, y: categories }
+    } else {
+      DataBounds { x: categories, y: values }
     })
   }
 
+  /// Points spread along the middle of each bar.
+  pub(crate) fn points(&self, transform: &ViewportTransform) -> Vec<Point> {
+    let categories =
+      if self.horizontal { &transform.y_categories } else { &transform.x_categories };
+    (0..self.labels.len())
+      .filter_map(|i| {
+        let label = self.labels.get(i).ok()?;
+        let value = self.values.get(i).and_then(|v| v.try_extract::<f64>()).ok()?;
+        let at = bounds::position(categories, RangeUnit::Absolute, label).unwrap_or(i as f64);
+        Some((0..=4).map(move |j| {
+          let value = value * f64::from(j) / 4.0;
+          if self.horizontal { Point::new(value, at) } else { Point::new(at, value) }
+        }))
+      })
+      .flatten()
+      .collect()
+  }
+
   pub(crate) fn draw(&self, render: &mut Render, transform: &ViewportTransform) {
     let mut fill = BezPath::new();
 
-    for x in 0..self.labels.len() {
+    for i in 0..self.labels.len() {
       const WIDTH: f64 = 0.3;
-      let Some(value) = self.values.get(x).and_then(|v| v.try_extract::<f64>()).log_err() else {
+      let Some(label) = self.labels.get(i).log_err() else { continue };
+      let Some(value) = self.values.get(i).and_then(|v| v.try_extract::<f64>()).log_err() else {
         continue;
       };
+      let (categories, point): (_, fn(f64, f64) -> Point) = if self.horizontal {
+        (&transform.y_categories, |at, value| Point::new(value, at))
+      } else {
+        (&transform.x_categories, |at, value| Point::new(at, value))
+      };
+      let at = bounds::position(categories, RangeUnit::Absolute, label).unwrap_or(i as f64);
 
-      fill.move_to(Point::new(x as f64 - WIDTH, 0.0));
-      fill.line_to(Point::new(x as f64 - WIDTH, value));
-      fill.line_to(Point::new(x as f64 + WIDTH, value));
-      fill.line_to(Point::new(x as f64 + WIDTH, 0.0));
-      fill.line_to(Point::new(x as f64 - WIDTH, 0.0));
+      fill.move_to(point(at - WIDTH, 0.0));
+      fill.line_to(point(at - WIDTH, value));
+      fill.line_to(point(at + WIDTH, value));
+      fill.line_to(point(at + WIDTH, 0.0));
+      fill.line_to(point(at - WIDTH, 0.0));
     }
 
     render.fill(&(transform * fill), Affine::IDENTITY, crate::theme::ROCKET.sample(0.0));
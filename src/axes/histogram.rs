@@ -6,13 +6,18 @@ use polars::prelude::*;
 
 use crate::{
   Range, ResultExt,
+  axes::AxisBinding,
   bounds::{DataBounds, DataRange, RangeUnit, ViewportTransform},
   render::Render,
 };
 
 pub struct HistogramAxes<'a> {
+  name:   PlSmallStr,
   range:  Range,
   counts: Cow<'a, Column>,
+  label:  Option<String>,
+
+  pub(crate) binding: AxisBinding,
 }
 
 impl<'a> HistogramAxes<'a> {
@@ -34,13 +39,52 @@ impl<'a> HistogramAxes<'a> {
       counts[index] += 1;
     }
 
-    HistogramAxes { range, counts: Cow::Owned(Column::new("counts".into(), counts)) }
+    HistogramAxes {
+      name: values.name().clone(),
+      range,
+      counts: Cow::Owned(Column::new("counts".into(), counts)),
+      label: None,
+      binding: AxisBinding::default(),
+    }
   }
 
   pub(crate) fn new_counted(counts: &'a Column) -> Self {
-    HistogramAxes { range: Range::new(0.0, counts.len() as f64), counts: Cow::Borrowed(counts) }
+    HistogramAxes {
+      name:    counts.name().clone(),
+      range:   Range::new(0.0, counts.len() as f64),
+      counts:  Cow::Borrowed(counts),
+      label:   None,
+      binding: AxisBinding::default(),
+    }
+  }
+
+  /// Draws this series against the plot's secondary x axis. See
+  /// [`Plot::x2`](crate::Plot::x2).
+  pub fn on_x2(&mut self) -> &mut Self {
+    self.binding.x2 = true;
+    self
+  }
+
+  /// Draws this series against the plot's secondary y axis. See
+  /// [`Plot::y2`](crate::Plot::y2).
+  pub fn on_y2(&mut self) -> &mut Self {
+    self.binding.y2 = true;
+    self
   }
 
+  /// Names this series in the legend. Defaults to the name of the values
+  /// column.
+  pub fn label(&mut self, label: &str) -> &mut Self {
+    self.label = Some(label.to_string());
+    self
+  }
+
+  pub(crate) fn legend_label(&self) -> String {
+    self.label.clone().unwrap_or_else(|| self.name.to_string())
+  }
+
+  pub(crate) fn describe(&self) -> String { format!("histogram of `{}`", self.name) }
+
   pub(crate) fn data_bounds(&self) -> PolarsResult<DataBounds<'_>> {
     Ok(DataBounds {
       x: DataRange::Continuous {
@@ -62,6 +106,19 @@ impl<'a> HistogramAxes<'a> {
     })
   }
 
+  /// Points spread up the middle of each bin.
+  pub(crate) fn points(&self) -> Vec<Point> {
+    let width = self.range.size() / self.counts.len() as f64;
+    (0..self.counts.len())
+      .filter_map(|i| {
+        let count = self.counts.get(i).and_then(|c| c.try_extract::<f64>()).ok()?;
+        let x = self.range.min + (i as f64 + 0.5) * width;
+        Some((0..=4).map(move |j| Point::new(x, count * f64::from(j) / 4.0)))
+      })
+      .flatten()
+      .collect()
+  }
+
   pub(crate) fn draw(&self, render: &mut Render, transform: &ViewportTransform) {
     let mut outline = BezPath::new();
     let mut fill = BezPath::new();
@@ -4,14 +4,18 @@ use polars::prelude::*;
 
 use crate::{
   ResultExt,
+  axes::AxisBinding,
   bounds::{DataBounds, DataRange, ViewportTransform},
   render::Render,
 };
 
 pub struct LineAxes<'a> {
-  x:       &'a Column,
-  y:       &'a Column,
-  options: LineOptions,
+  x:                  &'a Column,
+  y:                  &'a Column,
+  pub(crate) options: LineOptions,
+  label:              Option<String>,
+
+  pub(crate) binding: AxisBinding,
 }
 
 #[derive(Clone)]
@@ -29,26 +33,59 @@ impl Default for LineOptions {
 
 impl<'a> LineAxes<'a> {
   pub(crate) fn new(x: &'a Column, y: &'a Column) -> Self {
-    LineAxes { x, y, options: LineOptions::default() }
+    LineAxes { x, y, options: LineOptions::default(), label: None, binding: AxisBinding::default() }
+  }
+
+  /// Draws this series against the plot's secondary x axis. See
+  /// [`Plot::x2`](crate::Plot::x2).
+  pub fn on_x2(&mut self) -> &mut Self {
+    self.binding.x2 = true;
+    self
+  }
+
+  /// Draws this series against the plot's secondary y axis. See
+  /// [`Plot::y2`](crate::Plot::y2).
+  pub fn on_y2(&mut self) -> &mut Self {
+    self.binding.y2 = true;
+    self
+  }
+
+  /// Names this series in the legend. Defaults to the name of the y
+  /// column.
+  pub fn label(&mut self, label: &str) -> &mut Self {
+    self.label = Some(label.to_string());
+    self
+  }
+
+  pub(crate) fn legend_label(&self) -> String {
+    self.label.clone().unwrap_or_else(|| self.y.name().to_string())
+  }
+
+  pub(crate) fn describe(&self) -> String {
+    format!("line of `{}` against `{}`", self.y.name(), self.x.name())
   }
 
   pub(crate) fn data_bounds(&self) -> PolarsResult<DataBounds<'_>> {
     Ok(DataBounds { x: DataRange::from_column(self.x)?, y: DataRange::from_column(self.y)? })
   }
 
-  fn iter<'b>(&'b self) -> impl Iterator<Item = PolarsResult<Point>> + 'b {
-    (0..self.x.len()).map(move |i| {
-      let x = self.x.get(i)?.try_extract::<f64>()?;
-      let y = self.y.get(i)?.try_extract::<f64>()?;
+  fn iter<'b>(
+    &'b self,
+    transform: &'b ViewportTransform,
+  ) -> impl Iterator<Item = PolarsResult<Point>> + 'b {
+    (0..self.x.len()).map(move |i| transform.data_point(self.x.get(i)?, self.y.get(i)?))
+  }
 
-      Ok(Point::new(x, y))
-    })
+  pub(crate) fn points(&self, transform: &ViewportTransform) -> Vec<Point> {
+    self.iter(transform).filter_map(Result::ok).collect()
   }
 
   pub(crate) fn draw(&self, render: &mut Render, transform: &ViewportTransform) {
     let mut shape = BezPath::new();
 
-    for (i, point) in self.iter().filter_map(|p| p.log_err()).map(|p| transform * p).enumerate() {
+    for (i, point) in
+      self.iter(transform).filter_map(|p| p.log_err()).map(|p| transform * p).enumerate()
+    {
       if i == 0 {
         shape.move_to(point);
       } else {
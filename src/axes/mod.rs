@@ -21,6 +21,13 @@ pub enum Axes<'a> {
   BarChart(BarChartAxes<'a>),
 }
 
+/// Which of the plot's axes a series is drawn against.
+#[derive(Default, Clone, Copy)]
+pub(crate) struct AxisBinding {
+  pub(crate) x2: bool,
+  pub(crate) y2: bool,
+}
+
 impl Axes<'_> {
   pub fn data_bounds(&self) -> PolarsResult<DataBounds<'_>> {
     match self {
@@ -31,6 +38,36 @@ impl Axes<'_> {
     }
   }
 
+  /// Names this series in error messages.
+  pub(crate) fn describe(&self) -> String {
+    match self {
+      Axes::Scatter(a) => a.describe(),
+      Axes::Line(a) => a.describe(),
+      Axes::Histogram(a) => a.describe(),
+      Axes::BarChart(a) => a.describe(),
+    }
+  }
+
+  pub(crate) fn binding(&self) -> AxisBinding {
+    match self {
+      Axes::Scatter(a) => a.binding,
+      Axes::Line(a) => a.binding,
+      Axes::Histogram(a) => a.binding,
+      Axes::BarChart(a) => a.binding,
+    }
+  }
+
+  /// Points covering this series, in data space, for finding the emptiest
+  /// part of the plot.
+  pub(crate) fn points(&self, transform: &ViewportTransform) -> Vec<kurbo::Point> {
+    match self {
+      Axes::Scatter(a) => a.points(transform),
+      Axes::Line(a) => a.points(transform),
+      Axes::Histogram(a) => a.points(),
+      Axes::BarChart(a) => a.points(transform),
+    }
+  }
+
   pub(crate) fn draw(&self, render: &mut crate::render::Render, transform: &ViewportTransform) {
     match self {
       Axes::Scatter(a) => a.draw(render, transform),
@@ -82,4 +119,11 @@ impl<'a> Plot<'a> {
       _ => unreachable!(),
     }
   }
+
+  /// Draws a bar chart with the categories on the y axis, and bars running to
+  /// the right. The first category is at the bottom, unless the y axis is
+  /// inverted.
+  pub fn barh(&mut self, labels: &'a Column, values: &'a Column) -> &mut BarChartAxes<'a> {
+    self.bar_chart(labels, values).horizontal()
+  }
 }
@@ -6,6 +6,7 @@ use polars::prelude::*;
 
 use crate::{
   LineOptions, Marker, ResultExt,
+  axes::AxisBinding,
   bounds::{DataBounds, DataRange, ViewportTransform},
   render::Render,
 };
@@ -14,9 +15,12 @@ pub struct ScatterAxes<'a> {
   x:                  &'a Column,
   y:                  &'a Column,
   pub(crate) options: ScatterOptions,
+  label:              Option<String>,
 
   hue_column:          Option<&'a Column>,
   pub(crate) hue_keys: Option<Vec<AnyValue<'a>>>,
+
+  pub(crate) binding: AxisBinding,
 }
 
 pub struct ScatterOptions {
@@ -52,7 +56,44 @@ impl Default for ScatterOptions {
 
 impl<'a> ScatterAxes<'a> {
   pub(crate) fn new(x: &'a Column, y: &'a Column) -> Self {
-    ScatterAxes { x, y, options: ScatterOptions::default(), hue_column: None, hue_keys: None }
+    ScatterAxes {
+      x,
+      y,
+      options: ScatterOptions::default(),
+      label: None,
+      hue_column: None,
+      hue_keys: None,
+      binding: AxisBinding::default(),
+    }
+  }
+
+  /// Draws this series against the plot's secondary x axis. See
+  /// [`Plot::x2`](crate::Plot::x2).
+  pub fn on_x2(&mut self) -> &mut Self {
+    self.binding.x2 = true;
+    self
+  }
+
+  /// Draws this series against the plot's secondary y axis. See
+  /// [`Plot::y2`](crate::Plot::y2).
+  pub fn on_y2(&mut self) -> &mut Self {
+    self.binding.y2 = true;
+    self
+  }
+
+  /// Names this series in the legend. Defaults to the name of the y
+  /// column.
+  pub fn label(&mut self, label: &str) -> &mut Self {
+    self.label = Some(label.to_string());
+    self
+  }
+
+  pub(crate) fn legend_label(&self) -> String {
+    self.label.clone().unwrap_or_else(|| self.y.name().to_string())
+  }
+
+  pub(crate) fn describe(&self) -> String {
+    format!("scatter of `{}` against `{}`", self.y.name(), self.x.name())
   }
 
   pub(crate) fn data_bounds(&self) -> PolarsResult<DataBounds<'_>> {
@@ -97,13 +138,15 @@ impl<'a> ScatterAxes<'a> {
     self.options.trendline.as_mut().unwrap()
   }
 
-  fn iter<'b>(&'b self) -> impl Iterator<Item = PolarsResult<Point>> + 'b {
-    (0..self.x.len()).map(move |i| {
-      let x = self.x.get(i)?.try_extract::<f64>()?;
-      let y = self.y.get(i)?.try_extract::<f64>()?;
+  fn iter<'b>(
+    &'b self,
+    transform: &'b ViewportTransform,
+  ) -> impl Iterator<Item = PolarsResult<Point>> + 'b {
+    (0..self.x.len()).map(move |i| transform.data_point(self.x.get(i)?, self.y.get(i)?))
+  }
 
-      Ok(Point::new(x, y))
-    })
+  pub(crate) fn points(&self, transform: &ViewportTransform) -> Vec<Point> {
+    self.iter(transform).filter_map(Result::ok).collect()
   }
 
   pub(crate) fn hues(&self) -> Option<Vec<AnyValue<'static>>> {
@@ -128,7 +171,9 @@ impl<'a> ScatterAxes<'a> {
 
     let shape = self.options.marker.to_path(0.1);
 
-    for (i, point) in self.iter().filter_map(|p| p.log_err()).map(|p| transform * p).enumerate() {
+    for (i, point) in
+      self.iter(transform).filter_map(|p| p.log_err()).map(|p| transform * p).enumerate()
+    {
       let color = if let Some(ref hues) = hues {
         let v = self.hue_column.as_ref().unwrap().get(i).unwrap();
 
@@ -143,52 +188,43 @@ impl<'a> ScatterAxes<'a> {
       render.fill(&shape, Affine::scale(self.options.size).then_translate(point.to_vec2()), &color);
     }
 
-    if let Some(trendline) = &self.options.trendline {
-      trendline.draw(self.x, self.y, render, transform).log_err();
+    if let Some(trendline) = &self.options.trendline
+      && let Some(line) = self.trendline_line(transform)
+    {
+      render.stroke(
+        &(transform * line),
+        Affine::IDENTITY,
+        &trendline.line.color,
+        &trendline.line.stroke(),
+      );
     }
   }
-}
 
-impl TrendlineOptions {
-  fn draw(
-    &self,
-    x: &Column,
-    y: &Column,
-    render: &mut Render,
-    transform: &ViewportTransform,
-  ) -> PolarsResult<()> {
-    let df =
-      DataFrame::new(vec![x.clone().with_name("x".into()), y.clone().with_name("y".into())])?;
-    let stats = df
-      .lazy()
-      .select([
-        cov(col("x"), col("y"), 1).alias("cov_xy"),
-        col("x").var(1).alias("var_x"),
-        col("x").mean().alias("mean_x"),
-        col("y").mean().alias("mean_y"),
-      ])
-      .collect()?;
-
-    let s_cov = stats.column("cov_xy")?.f64()?.get(0).unwrap();
-    let s_var = stats.column("var_x")?.f64()?.get(0).unwrap();
-    let mean_x = stats.column("mean_x")?.f64()?.get(0).unwrap();
-    let mean_y = stats.column("mean_y")?.f64()?.get(0).unwrap();
-
-    let slope = s_cov / s_var;
-    let intercept = mean_y - slope * mean_x;
-
-    let p0 = Point::new(
-      x.min_reduce()?.into_value().try_extract::<f64>()?,
-      x.min_reduce()?.into_value().try_extract::<f64>()? * slope + intercept,
-    );
-    let p1 = Point::new(
-      x.max_reduce()?.into_value().try_extract::<f64>()?,
-      x.max_reduce()?.into_value().try_extract::<f64>()? * slope + intercept,
-    );
+  /// The fitted trendline in data space, across the range of the points. The
+  /// fit uses the same converted positions as the markers, so dates and
+  /// categories line up with them. `None` if there is no trendline, or too
+  /// few distinct x values to fit one.
+  pub(crate) fn trendline_line(&self, transform: &ViewportTransform) -> Option<Line> {
+    self.options.trendline.as_ref()?;
+    let points = self.points(transform);
+    if points.len() < 2 {
+      return None;
+    }
 
-    let line = Line::new(p0, p1);
-    render.stroke(&(transform * line), Affine::IDENTITY, &self.line.color, &self.line.stroke());
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.y).sum::<f64>() / n;
+    let cov_xy = points.iter().map(|p| (p.x - mean_x) * (p.y - mean_y)).sum::<f64>();
+    let var_x = points.iter().map(|p| (p.x - mean_x).powi(2)).sum::<f64>();
+    if var_x == 0.0 {
+      return None;
+    }
 
-    Ok(())
+    let slope = cov_xy / var_x;
+    let intercept = mean_y - slope * mean_x;
+    let at = |x: f64| Point::new(x, x * slope + intercept);
+    let min_x = points.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
+    let max_x = points.iter().map(|p| p.x).fold(f64::NEG_INFINITY, f64::max);
+    Some(Line::new(at(min_x), at(max_x)))
   }
 }
@@ -1,9 +1,13 @@
-use std::ops::Mul;
+use std::{borrow::Cow, collections::HashMap, ops::Mul};
 
+use chrono_tz::Tz;
 use kurbo::{Affine, BezPath, Line, PathEl, Point};
-use polars::{error::PolarsResult, prelude::Column};
+use polars::{
+  error::PolarsResult,
+  prelude::{AnyValue, Column, TimeUnit},
+};
 
-use crate::Scale;
+use crate::{ResultExt, Scale, calendar};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Bounds {
@@ -11,29 +15,82 @@ pub struct Bounds {
   pub y: Range,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct DataBounds<'a> {
   pub x: DataRange<'a>,
   pub y: DataRange<'a>,
 }
 
-pub(crate) struct ViewportTransform {
-  pub affine: Affine,
-  pub x:      Scale,
-  pub y:      Scale,
+/// Maps points from data space onto the screen, applying each axis's [`Scale`]
+/// before the affine viewport transform.
+pub struct ViewportTransform {
+  pub(crate) affine:   Affine,
+  pub(crate) x:        Scale,
+  pub(crate) y:        Scale,
+  pub(crate) x_breaks: Vec<Break>,
+  pub(crate) y_breaks: Vec<Break>,
+
+  /// The position of each category on a categorical axis, by name.
+  pub(crate) x_categories: HashMap<String, usize>,
+  pub(crate) y_categories: HashMap<String, usize>,
+
+  /// The units of each axis, which column values are converted into.
+  pub(crate) x_unit: RangeUnit,
+  pub(crate) y_unit: RangeUnit,
 }
 
-#[derive(Clone, Copy)]
+/// An interval of an axis that is collapsed down to a small gap. Everything is
+/// in scaled space.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Break {
+  pub(crate) from: f64,
+  pub(crate) to:   f64,
+  pub(crate) gap:  f64,
+}
+
+#[derive(Clone)]
 pub enum DataRange<'a> {
-  Continuous { range: Range, unit: RangeUnit, margin_min: bool, margin_max: bool },
-  Categorical(&'a Column),
+  Continuous {
+    range:      Range,
+    unit:       RangeUnit,
+    margin_min: bool,
+    margin_max: bool,
+  },
+  /// Distinct categories, in the order they are placed along the axis.
+  Categorical(Vec<AnyValue<'a>>),
 }
 
 #[derive(Clone, Copy)]
 pub enum RangeUnit {
   Absolute,
-  Duration,
+  Duration(TimeUnit),
   Date,
+  /// Timestamps since the epoch, shown in the given time zone, or UTC.
+  Datetime(TimeUnit, Option<Tz>),
+}
+
+impl RangeUnit {
+  /// The unit of a single column value.
+  pub(crate) fn of(value: &AnyValue) -> RangeUnit {
+    match value {
+      AnyValue::Date(_) => RangeUnit::Date,
+      AnyValue::Datetime(_, unit, _) | AnyValue::DatetimeOwned(_, unit, _) => {
+        RangeUnit::Datetime(*unit, None)
+      }
+      AnyValue::Duration(_, unit) => RangeUnit::Duration(*unit),
+      _ => RangeUnit::Absolute,
+    }
+  }
+
+  /// What values in this unit are, for error messages.
+  pub(crate) fn describe(&self) -> &'static str {
+    match self {
+      RangeUnit::Absolute => "numbers",
+      RangeUnit::Duration(_) => "durations",
+      RangeUnit::Date => "dates",
+      RangeUnit::Datetime(..) => "datetimes",
+    }
+  }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -91,6 +148,7 @@ impl Range {
   pub const fn empty() -> Self { Range { min: 0.0, max: 0.0 } }
   pub const fn new(min: f64, max: f64) -> Self { Range { min, max } }
   pub const fn size(&self) -> f64 { self.max - self.min }
+  pub const fn center(&self) -> f64 { (self.min + self.max) / 2.0 }
 
   pub const fn shrink(self, amount: f64) -> Self { self.expand(-amount) }
   pub const fn shrink_by(self, fract: f64) -> Self { self.shrink(self.size() * fract) }
@@ -116,8 +174,14 @@ impl Range {
     }
   }
 
+  /// Returns this range with `min <= max`.
+  pub fn ascending(self) -> Range {
+    if self.min <= self.max { self } else { Range { min: self.max, max: self.min } }
+  }
+
   pub fn nice_ticks(&self, count: u32) -> NiceTicksIter {
-    let step = (self.max - self.min) / f64::from(count);
+    let range = self.ascending();
+    let step = range.size() / f64::from(count);
     let k = step.log10().floor();
     let base = step / 10f64.powf(k);
 
@@ -130,28 +194,104 @@ impl Range {
     };
 
     let step = nice_base * 10f64.powf(k);
-    let lo = (self.min / step).floor() * step;
-    let hi = (self.max / step).ceil() * step;
+    let lo = (range.min / step).floor() * step;
+    let hi = (range.max / step).ceil() * step;
 
     let precision = (-k as i32 + 4).max(0) as usize;
     NiceTicksIter::new(lo, hi, step, precision)
   }
 
+  /// Ticks for a symlog axis: zero, and every power of ten past `linthresh` on
+  /// either side of it. Decades are skipped evenly if there would be more than
+  /// `count` ticks.
+  pub(crate) fn symlog_ticks(&self, linthresh: f64, count: u32) -> Vec<f64> {
+    let largest = self.min.abs().max(self.max.abs());
+    let k_min = linthresh.log10().ceil() as i32;
+    let k_max = largest.log10().floor() as i32;
+
+    let sides = if self.contains(&0.0) { 2 } else { 1 };
+    let decades = (k_max - k_min + 1).max(1) as u32 * sides;
+    let stride = decades.div_ceil(count.max(1)).max(1) as usize;
+
+    let mut ticks = vec![];
+    if self.contains(&0.0) {
+      ticks.push(0.0);
+    }
+    for k in (k_min..=k_max).step_by(stride) {
+      let v = 10_f64.powi(k);
+      if self.contains(&v) {
+        ticks.push(v);
+      }
+      if self.contains(&-v) {
+        ticks.push(-v);
+      }
+    }
+    ticks.sort_by(f64::total_cmp);
+    ticks
+  }
+
   pub(crate) fn map(&self, f: impl Fn(f64) -> f64) -> Range {
     Range { min: f(self.min), max: f(self.max) }
   }
 }
 
 impl DataRange<'_> {
-  pub(crate) fn from_column<'a, 'b>(column: &'a Column) -> PolarsResult<DataRange<'b>> {
+  /// The unit of this range. Categories are positioned by index, in plain
+  /// numbers.
+  pub(crate) fn unit(&self) -> RangeUnit {
+    match self {
+      DataRange::Continuous { unit, .. } => *unit,
+      DataRange::Categorical(_) => RangeUnit::Absolute,
+    }
+  }
+
+  /// What the values in this range are, for error messages.
+  pub(crate) fn describe(&self) -> &'static str {
+    match self {
+      DataRange::Continuous { unit, .. } => unit.describe(),
+      DataRange::Categorical(_) => "categories",
+    }
+  }
+
+  /// The names of this range's categories, or nothing if it is continuous.
+  pub(crate) fn categories(&self) -> Vec<String> {
+    match self {
+      DataRange::Continuous { .. } => vec![],
+      DataRange::Categorical(labels) => labels.iter().map(category_name).collect(),
+    }
+  }
+
+  /// The position of each of this range's categories, by name.
+  pub(crate) fn category_positions(&self) -> HashMap<String, usize> {
+    self.categories().into_iter().enumerate().map(|(i, name)| (name, i)).collect()
+  }
+
+  /// The distinct values of `column` as categories, in the order they are
+  /// first seen.
+  pub(crate) fn categorical(column: &Column) -> PolarsResult<DataRange<'static>> {
+    let unique = column.unique_stable()?;
+    Ok(DataRange::Categorical(
+      unique.as_materialized_series().iter().map(|v| v.into_static()).collect(),
+    ))
+  }
+
+  pub(crate) fn from_column(column: &Column) -> PolarsResult<DataRange<'static>> {
+    let dtype = column.dtype();
+    if dtype.is_string() || dtype.is_categorical() || dtype.is_enum() {
+      return DataRange::categorical(column);
+    }
+
     Ok(DataRange::Continuous {
       range:      Range::new(
         column.min_reduce()?.into_value().try_extract::<f64>()?,
         column.max_reduce()?.into_value().try_extract::<f64>()?,
       ),
       unit:       match column.dtype() {
-        polars::prelude::DataType::Duration(_) => RangeUnit::Duration,
+        polars::prelude::DataType::Duration(unit) => RangeUnit::Duration(*unit),
         polars::prelude::DataType::Date => RangeUnit::Date,
+        polars::prelude::DataType::Datetime(unit, tz) => {
+          RangeUnit::Datetime(*unit, tz.as_ref().and_then(|tz| tz.to_chrono().log_err()))
+        }
         _ => RangeUnit::Absolute,
       },
       margin_min: true,
@@ -161,25 +301,134 @@ impl DataRange<'_> {
 }
 
 impl Scale {
-  pub(crate) fn scale_value(&self, value: f64) -> f64 {
+  /// Maps a data value into the scaled space the viewport is linear in.
+  pub fn scale_value(&self, value: f64) -> f64 {
     match self {
       Scale::Linear => value,
-      Scale::Logarithmic => {
+      Scale::Logarithmic { base } => {
         if value <= 0.0 {
           0.0
         } else {
-          value.log10()
+          value.log(*base)
+        }
+      }
+      // One unit covers the linear region on each side of zero, and every
+      // decade past it.
+      Scale::SymLog { linthresh } => {
+        let x = value.abs() / linthresh;
+        if x <= 1.0 { value / linthresh } else { value.signum() * (1.0 + x.log10()) }
+      }
+      Scale::Custom(scale) => scale.forward(value),
+    }
+  }
+
+  /// The inverse of [`Scale::scale_value`].
+  pub fn unscale_value(&self, value: f64) -> f64 {
+    match self {
+      Scale::Linear => value,
+      Scale::Logarithmic { base } => base.powf(value),
+      Scale::SymLog { linthresh } => {
+        if value.abs() <= 1.0 {
+          value * linthresh
+        } else {
+          value.signum() * linthresh * 10_f64.powf(value.abs() - 1.0)
         }
       }
+      Scale::Custom(scale) => scale.inverse(value),
+    }
+  }
+}
+
+impl ViewportTransform {
+  /// The data-space point for a pair of column values. Values on a categorical
+  /// axis are placed at their category's position.
+  pub(crate) fn data_point(&self, x: AnyValue, y: AnyValue) -> PolarsResult<Point> {
+    Ok(Point::new(
+      position(&self.x_categories, self.x_unit, x)?,
+      position(&self.y_categories, self.y_unit, y)?,
+    ))
+  }
+
+  /// Maps a point on the screen back into data space. This is the inverse of
+  /// multiplying a data point by this transform.
+  pub(crate) fn inverse(&self, point: Point) -> Point {
+    let p = self.affine.inverse() * point;
+    Point::new(
+      self.x.unscale_value(expand(&self.x_breaks, p.x)),
+      self.y.unscale_value(expand(&self.y_breaks, p.y)),
+    )
+  }
+}
+
+/// The position of `value` along an axis with the given categories, or in the
+/// given unit if the axis is continuous.
+pub(crate) fn position(
+  categories: &HashMap<String, usize>,
+  unit: RangeUnit,
+  value: AnyValue,
+) -> PolarsResult<f64> {
+  if !categories.is_empty()
+    && let Some(&index) = categories.get(category_key(&value).as_ref())
+  {
+    return Ok(index as f64);
+  }
+  Ok(calendar::convert(value.try_extract::<f64>()?, RangeUnit::of(&value), unit))
+}
+
+/// The text a category is labeled with, which also identifies it when
+/// categories from several series are merged.
+pub(crate) fn category_name(value: &AnyValue) -> String { category_key(value).into_owned() }
+
+/// Like [`category_name`], but borrows the text of string values.
+fn category_key<'v>(value: &'v AnyValue) -> Cow<'v, str> {
+  match value {
+    AnyValue::String(s) => Cow::Borrowed(s),
+    AnyValue::StringOwned(s) => Cow::Borrowed(s.as_str()),
+    _ => Cow::Owned(value.to_string()),
+  }
+}
+
+/// Collapses every one of `breaks`, which must be sorted and not overlap, down
+/// to its gap. Values inside a break are squeezed into the gap.
+pub(crate) fn collapse(breaks: &[Break], value: f64) -> f64 {
+  let mut removed = 0.0;
+  for b in breaks {
+    if value >= b.to {
+      removed += b.to - b.from - b.gap;
+    } else if value > b.from {
+      return b.from - removed + (value - b.from) / (b.to - b.from) * b.gap;
+    } else {
+      break;
+    }
+  }
+  value - removed
+}
+
+/// The inverse of [`collapse`].
+pub(crate) fn expand(breaks: &[Break], value: f64) -> f64 {
+  let mut removed = 0.0;
+  for b in breaks {
+    let start = b.from - removed;
+    if value >= start + b.gap {
+      removed += b.to - b.from - b.gap;
+    } else if value > start {
+      return b.from + (value - start) / b.gap * (b.to - b.from);
+    } else {
+      break;
     }
   }
+  value + removed
 }
 
 impl Mul<Point> for &ViewportTransform {
   type Output = Point;
 
   fn mul(self, rhs: Point) -> Self::Output {
-    self.affine * Point::new(self.x.scale_value(rhs.x), self.y.scale_value(rhs.y))
+    self.affine
+      * Point::new(
+        collapse(&self.x_breaks, self.x.scale_value(rhs.x)),
+        collapse(&self.y_breaks, self.y.scale_value(rhs.y)),
+      )
   }
 }
 
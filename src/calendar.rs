@@ -0,0 +1,375 @@
+use chrono::{
+  DateTime, Datelike, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, TimeZone, Timelike,
+};
+use chrono_tz::Tz;
+use polars::prelude::TimeUnit;
+
+use crate::{Range, bounds::RangeUnit};
+
+/// The distance between two ticks on a time axis. Steps are applied in local
+/// time, so that ticks land on calendar boundaries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TimeStep {
+  Millis(i64),
+  Seconds(i64),
+  Minutes(i64),
+  Hours(i64),
+  Days(i64),
+  Weeks(i64),
+  Months(u32),
+  Years(i32),
+}
+
+const STEPS: &[TimeStep] = &[
+  TimeStep::Millis(1),
+  TimeStep::Millis(2),
+  TimeStep::Millis(5),
+  TimeStep::Millis(10),
+  TimeStep::Millis(20),
+  TimeStep::Millis(50),
+  TimeStep::Millis(100),
+  TimeStep::Millis(200),
+  TimeStep::Millis(500),
+  TimeStep::Seconds(1),
+  TimeStep::Seconds(2),
+  TimeStep::Seconds(5),
+  TimeStep::Seconds(10),
+  TimeStep::Seconds(15),
+  TimeStep::Seconds(30),
+  TimeStep::Minutes(1),
+  TimeStep::Minutes(2),
+  TimeStep::Minutes(5),
+  TimeStep::Minutes(10),
+  TimeStep::Minutes(15),
+  TimeStep::Minutes(30),
+  TimeStep::Hours(1),
+  TimeStep::Hours(2),
+  TimeStep::Hours(3),
+  TimeStep::Hours(6),
+  TimeStep::Hours(12),
+  TimeStep::Days(1),
+  TimeStep::Days(2),
+  TimeStep::Weeks(1),
+  TimeStep::Weeks(2),
+  TimeStep::Months(1),
+  TimeStep::Months(3),
+  TimeStep::Months(6),
+  TimeStep::Years(1),
+  TimeStep::Years(2),
+  TimeStep::Years(5),
+  TimeStep::Years(10),
+  TimeStep::Years(20),
+  TimeStep::Years(50),
+  TimeStep::Years(100),
+];
+
+impl TimeStep {
+  /// The rough length of this step, used to pick a step for a range.
+  fn approx_seconds(&self) -> f64 {
+    match *self {
+      TimeStep::Millis(n) => n as f64 / 1000.0,
+      TimeStep::Seconds(n) => n as f64,
+      TimeStep::Minutes(n) => n as f64 * 60.0,
+      TimeStep::Hours(n) => n as f64 * 3600.0,
+      TimeStep::Days(n) => n as f64 * 86400.0,
+      TimeStep::Weeks(n) => n as f64 * 604800.0,
+      TimeStep::Months(n) => f64::from(n) * 2629746.0,
+      TimeStep::Years(n) => f64::from(n) * 31556952.0,
+    }
+  }
+
+  /// The smallest step of at least `min` that gives at most `count` ticks over
+  /// `seconds`.
+  fn pick(seconds: f64, count: u32, min: TimeStep) -> TimeStep {
+    STEPS
+      .iter()
+      .copied()
+      .filter(|step| step.approx_seconds() >= min.approx_seconds())
+      .find(|step| seconds / step.approx_seconds() <= f64::from(count))
+      .unwrap_or(TimeStep::Years(1000))
+  }
+
+  /// Rounds `t` down to the nearest tick boundary.
+  fn floor(&self, t: NaiveDateTime) -> NaiveDateTime {
+    let date = t.date();
+    let seconds = |n: i64| {
+      let s = i64::from(t.num_seconds_from_midnight());
+      let s = s - s.rem_euclid(n);
+      date.and_time(NaiveTime::from_num_seconds_from_midnight_opt(s as u32, 0).unwrap())
+    };
+
+    match *self {
+      TimeStep::Millis(n) => {
+        let ms = t.and_utc().timestamp_millis();
+        DateTime::from_timestamp_millis(ms - ms.rem_euclid(n)).unwrap_or_default().naive_utc()
+      }
+      TimeStep::Seconds(n) => seconds(n),
+      TimeStep::Minutes(n) => seconds(n * 60),
+      TimeStep::Hours(n) => seconds(n * 3600),
+      TimeStep::Days(n) => {
+        let days = i64::from(date.num_days_from_ce());
+        let days = days - days.rem_euclid(n);
+        NaiveDate::from_num_days_from_ce_opt(days as i32).unwrap_or(date).into()
+      }
+      TimeStep::Weeks(n) => {
+        // Weeks are counted from 0001-01-01, which was a Monday, so steps of
+        // several weeks line up wherever the range starts.
+        let days = i64::from(date.num_days_from_ce()) - 1;
+        let days = days - days.rem_euclid(n * 7);
+        NaiveDate::from_num_days_from_ce_opt(days as i32 + 1).unwrap_or(date).into()
+      }
+      TimeStep::Months(n) => {
+        let month = date.month0() - date.month0() % n;
+        NaiveDate::from_ymd_opt(date.year(), month + 1, 1).unwrap().into()
+      }
+      TimeStep::Years(n) => {
+        NaiveDate::from_ymd_opt(date.year() - date.year().rem_euclid(n), 1, 1).unwrap().into()
+      }
+    }
+  }
+
+  fn advance(&self, t: NaiveDateTime) -> Option<NaiveDateTime> {
+    match *self {
+      TimeStep::Millis(n) => t.checked_add_signed(TimeDelta::milliseconds(n)),
+      TimeStep::Seconds(n) => t.checked_add_signed(TimeDelta::seconds(n)),
+      TimeStep::Minutes(n) => t.checked_add_signed(TimeDelta::minutes(n)),
+      TimeStep::Hours(n) => t.checked_add_signed(TimeDelta::hours(n)),
+      TimeStep::Days(n) => t.checked_add_signed(TimeDelta::days(n)),
+      TimeStep::Weeks(n) => t.checked_add_signed(TimeDelta::weeks(n)),
+      TimeStep::Months(n) => t.checked_add_months(Months::new(n)),
+      TimeStep::Years(n) => t.checked_add_months(Months::new(n as u32 * 12)),
+    }
+  }
+}
+
+fn units_per_second(unit: TimeUnit) -> f64 {
+  match unit {
+    TimeUnit::Nanoseconds => 1e9,
+    TimeUnit::Microseconds => 1e6,
+    TimeUnit::Milliseconds => 1e3,
+  }
+}
+
+fn to_local(value: f64, unit: TimeUnit, tz: Option<Tz>) -> NaiveDateTime {
+  let v = value as i64;
+  let utc = match unit {
+    TimeUnit::Nanoseconds => Some(DateTime::from_timestamp_nanos(v)),
+    TimeUnit::Microseconds => DateTime::from_timestamp_micros(v),
+    TimeUnit::Milliseconds => DateTime::from_timestamp_millis(v),
+  }
+  .unwrap_or_default();
+  utc.with_timezone(&tz.unwrap_or(chrono_tz::UTC)).naive_local()
+}
+
+fn from_local(t: NaiveDateTime, unit: TimeUnit, tz: Option<Tz>) -> Option<f64> {
+  let tz = tz.unwrap_or(chrono_tz::UTC);
+  // Local times skipped by a DST transition resolve to the hour after.
+  let t = tz
+    .from_local_datetime(&t)
+    .earliest()
+    .or_else(|| tz.from_local_datetime(&t.checked_add_signed(TimeDelta::hours(1))?).earliest())?;
+  Some(match unit {
+    TimeUnit::Nanoseconds => t.timestamp_nanos_opt()? as f64,
+    TimeUnit::Microseconds => t.timestamp_micros() as f64,
+    TimeUnit::Milliseconds => t.timestamp_millis() as f64,
+  })
+}
+
+const MILLIS_PER_DAY: f64 = 86_400_000.0;
+
+/// The time unit, time zone and scale into that time unit for values of
+/// `unit`. Dates are days since the epoch, so they become milliseconds in UTC.
+fn timeline(unit: RangeUnit) -> Option<(TimeUnit, Option<Tz>, f64)> {
+  match unit {
+    RangeUnit::Date => Some((TimeUnit::Milliseconds, None, MILLIS_PER_DAY)),
+    RangeUnit::Datetime(time_unit, tz) => Some((time_unit, tz, 1.0)),
+    _ => None,
+  }
+}
+
+/// Ticks on calendar boundaries over `range`, which must be a
+/// [`RangeUnit::Date`] or [`RangeUnit::Datetime`] range.
+pub(crate) fn ticks(range: Range, unit: RangeUnit, count: u32) -> (TimeStep, Vec<f64>) {
+  let Some((time_unit, tz, scale)) = timeline(unit) else { return (TimeStep::Days(1), vec![]) };
+  let min = match unit {
+    RangeUnit::Date => TimeStep::Days(1),
+    _ => TimeStep::Millis(1),
+  };
+
+  let range = range.ascending().map(|v| v * scale);
+  let step = TimeStep::pick(range.size() / units_per_second(time_unit), count, min);
+
+  let mut ticks = vec![];
+  let mut t = Some(step.floor(to_local(range.min, time_unit, tz)));
+  while let Some(local) = t {
+    let Some(value) = from_local(local, time_unit, tz) else { break };
+    if value > range.max || ticks.len() > 1000 {
+      break;
+    }
+    if value >= range.min && ticks.last() != Some(&(value / scale)) {
+      ticks.push(value / scale);
+    }
+    t = step.advance(local);
+  }
+
+  (step, ticks)
+}
+
+/// The local calendar year of `value`.
+pub(crate) fn year(value: f64, unit: RangeUnit) -> Option<i32> {
+  let (time_unit, tz, scale) = timeline(unit)?;
+  Some(to_local(value * scale, time_unit, tz).year())
+}
+
+/// Formats a timestamp. With a `step`, only the detail that changes between
+/// ticks of that step is shown, and `show_year` adds the year on a second line
+/// for steps that would otherwise hide it.
+pub(crate) fn format(
+  value: f64,
+  unit: RangeUnit,
+  step: Option<TimeStep>,
+  show_year: bool,
+) -> String {
+  let Some((time_unit, tz, scale)) = timeline(unit) else { return String::new() };
+  let t = to_local(value * scale, time_unit, tz);
+  let midnight = t.time() == NaiveTime::MIN;
+
+  let fmt = match step {
+    None if matches!(unit, RangeUnit::Date) => "%Y-%m-%d",
+    None => "%Y-%m-%d %H:%M:%S",
+    Some(TimeStep::Millis(_)) => "%H:%M:%S%.3f",
+    Some(TimeStep::Seconds(_)) => "%H:%M:%S",
+    Some(TimeStep::Minutes(_) | TimeStep::Hours(_)) if midnight => "%b %-d",
+    Some(TimeStep::Minutes(_) | TimeStep::Hours(_)) => "%H:%M",
+    Some(TimeStep::Days(_) | TimeStep::Weeks(_)) if show_year => "%b %-d\n%Y",
+    Some(TimeStep::Days(_) | TimeStep::Weeks(_)) => "%b %-d",
+    Some(TimeStep::Months(_)) if show_year => "%b\n%Y",
+    Some(TimeStep::Months(_)) => "%b",
+    Some(TimeStep::Years(_)) => "%Y",
+  };
+  t.format(fmt).to_string()
+}
+
+fn nanos_per_unit(unit: TimeUnit) -> f64 { 1e9 / units_per_second(unit) }
+
+/// The unit that values of both `a` and `b` can be shown in, if they can share
+/// an axis at all. Dates become datetimes, and time units become the finer of
+/// the two.
+pub(crate) fn common_unit(a: RangeUnit, b: RangeUnit) -> Option<RangeUnit> {
+  let finer = |a: TimeUnit, b: TimeUnit| if nanos_per_unit(a) <= nanos_per_unit(b) { a } else { b };
+  match (a, b) {
+    (RangeUnit::Absolute, RangeUnit::Absolute) => Some(RangeUnit::Absolute),
+    (RangeUnit::Date, RangeUnit::Date) => Some(RangeUnit::Date),
+    (RangeUnit::Date, unit @ RangeUnit::Datetime(..))
+    | (unit @ RangeUnit::Datetime(..), RangeUnit::Date) => Some(unit),
+    (RangeUnit::Datetime(a, tz_a), RangeUnit::Datetime(b, tz_b)) => {
+      Some(RangeUnit::Datetime(finer(a, b), tz_a.or(tz_b)))
+    }
+    (RangeUnit::Duration(a), RangeUnit::Duration(b)) => Some(RangeUnit::Duration(finer(a, b))),
+    _ => None,
+  }
+}
+
+/// Converts `value` from one unit to another. Both must be time units, or the
+/// value is returned as is.
+pub(crate) fn convert(value: f64, from: RangeUnit, to: RangeUnit) -> f64 {
+  let nanos = |unit| match unit {
+    RangeUnit::Date => Some(MILLIS_PER_DAY * 1e6),
+    RangeUnit::Datetime(unit, _) | RangeUnit::Duration(unit) => Some(nanos_per_unit(unit)),
+    RangeUnit::Absolute => None,
+  };
+  match (nanos(from), nanos(to)) {
+    (Some(from), Some(to)) if from != to => value * from / to,
+    _ => value,
+  }
+}
+
+/// Ticks for a duration axis, on natural steps such as 10ms, 30s or 1h.
+pub(crate) fn duration_ticks(range: Range, unit: TimeUnit, count: u32) -> Vec<f64> {
+  const SECOND: f64 = 1e9;
+  const LONG_STEPS: &[f64] = &[
+    SECOND,
+    2.0 * SECOND,
+    5.0 * SECOND,
+    10.0 * SECOND,
+    15.0 * SECOND,
+    30.0 * SECOND,
+    60.0 * SECOND,
+    120.0 * SECOND,
+    300.0 * SECOND,
+    600.0 * SECOND,
+    900.0 * SECOND,
+    1800.0 * SECOND,
+    3600.0 * SECOND,
+    7200.0 * SECOND,
+    10800.0 * SECOND,
+    21600.0 * SECOND,
+    43200.0 * SECOND,
+    86400.0 * SECOND,
+    2.0 * 86400.0 * SECOND,
+    7.0 * 86400.0 * SECOND,
+    14.0 * 86400.0 * SECOND,
+  ];
+
+  let range = range.ascending().map(|v| v * nanos_per_unit(unit));
+  let count = f64::from(count.max(1));
+
+  // Below a second and above two weeks, steps are 1, 2 or 5 of a power of ten
+  // nanoseconds or days.
+  let nice = |v: f64| {
+    let k = 10_f64.powf(v.log10().floor());
+    [1.0, 2.0, 5.0, 10.0].into_iter().map(|m| m * k).find(|s| *s >= v).unwrap_or(10.0 * k)
+  };
+  let min_step = range.size() / count;
+  let step = if min_step <= SECOND / 2.0 {
+    nice(min_step).max(1.0)
+  } else if let Some(step) = LONG_STEPS.iter().copied().find(|s| *s >= min_step) {
+    step
+  } else {
+    nice(min_step / (86400.0 * SECOND)) * 86400.0 * SECOND
+  };
+
+  let mut ticks = vec![];
+  let mut i = (range.min / step).ceil();
+  while i * step <= range.max && ticks.len() <= 1000 {
+    ticks.push(i * step / nanos_per_unit(unit));
+    i += 1.0;
+  }
+  ticks
+}
+
+/// Formats a duration compactly, such as `1h30m`, `250ms` or `-1.5s`.
+pub(crate) fn format_duration(value: f64, unit: TimeUnit) -> String {
+  let nanos = (value * nanos_per_unit(unit)).round();
+  if nanos == 0.0 {
+    return "0".to_string();
+  }
+
+  let sign = if nanos < 0.0 { "-" } else { "" };
+  let nanos = nanos.abs();
+  let trim = |v: f64| format!("{v:.3}").trim_end_matches('0').trim_end_matches('.').to_string();
+
+  if nanos < 1e3 {
+    return format!("{sign}{}ns", trim(nanos));
+  } else if nanos < 1e6 {
+    return format!("{sign}{}µs", trim(nanos / 1e3));
+  } else if nanos < 1e9 {
+    return format!("{sign}{}ms", trim(nanos / 1e6));
+  }
+
+  let total = (nanos / 1e9).floor() as u64;
+  let fraction = nanos / 1e9 - total as f64;
+  let (days, hours, minutes, seconds) =
+    (total / 86400, total / 3600 % 24, total / 60 % 60, total % 60);
+
+  let mut out = sign.to_string();
+  for (amount, suffix) in [(days, "d"), (hours, "h"), (minutes, "m")] {
+    if amount != 0 {
+      out += &format!("{amount}{suffix}");
+    }
+  }
+  if seconds != 0 || fraction >= 5e-4 {
+    out += &format!("{}s", trim(seconds as f64 + fraction));
+  }
+  out
+}
@@ -0,0 +1,331 @@
+use kurbo::Point;
+use parley::FontWeight;
+use peniko::{Brush, Color};
+
+use polars::prelude::{PolarsResult, polars_err};
+
+use crate::{
+  Bounds, CellLayout, HiddenLabels, Margins, Plot, PlotBounds, PlotLayout, Range, ResultExt,
+  bounds::DataRange,
+  render::{Align, Draw, DrawText, Render},
+  title_height,
+};
+
+/// The font size of the suptitle.
+const TITLE_SIZE: f32 = 40.0;
+/// The space between the top of the figure and the suptitle.
+const TITLE_EDGE: f64 = 15.0;
+
+/// Several plots laid out in a grid, saved or shown as a single image.
+pub struct Figure<'a> {
+  rows:    usize,
+  columns: usize,
+  spacing: f64,
+  title:   Option<String>,
+  share_x: bool,
+  share_y: bool,
+
+  pub(crate) cells: Vec<Cell<'a>>,
+}
+
+pub(crate) struct Cell<'a> {
+  rows:    std::ops::Range<usize>,
+  columns: std::ops::Range<usize>,
+  plot:    Plot<'a>,
+}
+
+impl<'a> Figure<'a> {
+  pub fn new(rows: usize, columns: usize) -> Figure<'a> {
+    Figure {
+      rows,
+      columns,
+      spacing: 0.0,
+      title: None,
+      share_x: false,
+      share_y: false,
+      cells: Vec::new(),
+    }
+  }
+
+  /// Sets the title drawn across the top of the whole figure.
+  pub fn title(&mut self, title: &str) -> &mut Self {
+    self.title = Some(title.to_string());
+    self
+  }
+
+  /// Sets the gap between neighbouring cells, on top of each plot's own
+  /// margins.
+  pub fn spacing(&mut self, spacing: f64) -> &mut Self {
+    self.spacing = spacing;
+    self
+  }
+
+  /// Gives every plot the same x axis range, covering all of their data, and
+  /// only labels the ticks of the bottom plot in each column.
+  pub fn share_x(&mut self) -> &mut Self {
+    self.share_x = true;
+    self
+  }
+
+  /// Gives every plot the same y axis range, covering all of their data, and
+  /// only labels the ticks of the leftmost plot in each row.
+  pub fn share_y(&mut self) -> &mut Self {
+    self.share_y = true;
+    self
+  }
+
+  /// Returns the plot in the cell at `row` and `column`, counting from the top
+  /// left, creating it if needed.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the cell is outside the grid, or part of a larger span given to
+  /// [`Figure::plot_span`].
+  pub fn plot(&mut self, row: usize, column: usize) -> &mut Plot<'a> {
+    self.plot_span(row..row + 1, column..column + 1)
+  }
+
+  /// Returns the plot covering the given rows and columns, creating it if
+  /// needed.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the span is empty, reaches outside the grid, or overlaps
+  /// another plot's cells without covering exactly the same ones.
+  pub fn plot_span(
+    &mut self,
+    rows: std::ops::Range<usize>,
+    columns: std::ops::Range<usize>,
+  ) -> &mut Plot<'a> {
+    assert!(
+      !rows.is_empty()
+        && rows.end <= self.rows
+        && !columns.is_empty()
+        && columns.end <= self.columns,
+      "cell {rows:?}, {columns:?} is outside the {}x{} grid",
+      self.rows,
+      self.columns,
+    );
+
+    let index = match self.cells.iter().position(|c| c.rows == rows && c.columns == columns) {
+      Some(index) => index,
+      None => {
+        if let Some(other) =
+          self.cells.iter().find(|c| overlaps(&c.rows, &rows) && overlaps(&c.columns, &columns))
+        {
+          panic!(
+            "cell {rows:?}, {columns:?} overlaps the plot at {:?}, {:?}",
+            other.rows, other.columns,
+          );
+        }
+        self.cells.push(Cell { rows, columns, plot: Plot::new() });
+        self.cells.len() - 1
+      }
+    };
+    &mut self.cells[index].plot
+  }
+
+  /// The region of `outer` that the cell spanning `rows` and `columns` is
+  /// drawn into.
+  pub(crate) fn cell_bounds(
+    &self,
+    outer: Bounds,
+    rows: &std::ops::Range<usize>,
+    columns: &std::ops::Range<usize>,
+  ) -> Bounds {
+    let span = |range: Range, count: usize, cells: &std::ops::Range<usize>| {
+      // Bounds run downwards in y, so step from `max` towards `min`.
+      let (start, size) =
+        if range.size() < 0.0 { (range.max, -range.size()) } else { (range.min, range.size()) };
+      let cell = (size - self.spacing * (count - 1) as f64) / count as f64;
+      let from = start + cells.start as f64 * (cell + self.spacing);
+      let to = start + cells.end as f64 * (cell + self.spacing) - self.spacing;
+      (from, to)
+    };
+
+    let (left, right) = span(outer.x, self.columns, columns);
+    let (top, bottom) = span(outer.y, self.rows, rows);
+    Bounds::new(Range::new(left, right), Range::new(bottom, top))
+  }
+
+  /// The data ranges of each cell's plot, with the primary axes merged across
+  /// every plot where they are shared.
+  pub(crate) fn plot_bounds(&self) -> Vec<PlotBounds<'_>> {
+    let mut bounds = self.cells.iter().map(|cell| cell.plot.bounds()).collect::<Vec<_>>();
+
+    if self.share_x
+      && let Some(shared) =
+        shared_range(bounds.iter().map(|b| b.x.clone()), "x").log_err().flatten()
+    {
+      for (cell, bounds) in self.cells.iter().zip(&mut bounds) {
+        bounds.x = cell.plot.x.ordered(shared.clone());
+      }
+    }
+    if self.share_y
+      && let Some(shared) =
+        shared_range(bounds.iter().map(|b| b.y.clone()), "y").log_err().flatten()
+    {
+      for (cell, bounds) in self.cells.iter().zip(&mut bounds) {
+        bounds.y = cell.plot.y.ordered(shared.clone());
+      }
+    }
+
+    bounds
+  }
+
+  /// Checks every plot with [`Plot::check`], and that the plots sharing an
+  /// axis have compatible units along it.
+  pub fn check(&self) -> PolarsResult<()> {
+    for cell in &self.cells {
+      cell.plot.check()?;
+    }
+    let bounds = self.cells.iter().map(|cell| cell.plot.bounds()).collect::<Vec<_>>();
+    if self.share_x {
+      shared_range(bounds.iter().map(|b| b.x.clone()), "x")?;
+    }
+    if self.share_y {
+      shared_range(bounds.iter().map(|b| b.y.clone()), "y")?;
+    }
+    Ok(())
+  }
+
+  /// Tick labels that `cell` can leave off, because the plot below it (or to
+  /// its left) shares the axis and labels it already.
+  pub(crate) fn hidden_labels(&self, cell: &Cell) -> HiddenLabels {
+    HiddenLabels {
+      x: self.share_x
+        && self.cells.iter().any(|other| {
+          other.rows.start >= cell.rows.end && overlaps(&other.columns, &cell.columns)
+        }),
+      y: self.share_y
+        && self.cells.iter().any(|other| {
+          other.columns.end <= cell.columns.start && overlaps(&other.rows, &cell.rows)
+        }),
+    }
+  }
+
+  /// How each cell's plot is laid out. Plots that start (or end) in the same
+  /// column share the widest left (or right) margin among them, and likewise
+  /// for rows, so that their viewports line up.
+  fn cell_layouts(
+    &self,
+    render: &mut Render,
+    outer: Bounds,
+    bounds: &[PlotBounds],
+  ) -> Vec<PlotLayout> {
+    let mut layouts = self
+      .cells
+      .iter()
+      .map(|cell| CellLayout { hidden: self.hidden_labels(cell), ..Default::default() })
+      .collect::<Vec<_>>();
+    let first = self
+      .cells
+      .iter()
+      .zip(bounds)
+      .zip(&layouts)
+      .map(|((cell, bounds), layout)| {
+        let at = self.cell_bounds(outer, &cell.rows, &cell.columns);
+        cell.plot.layout(render, at, bounds, *layout)
+      })
+      .collect::<Vec<_>>();
+    let margins = first.iter().map(|layout| layout.margins).collect::<Vec<_>>();
+
+    for ((cell, layout), first) in self.cells.iter().zip(&mut layouts).zip(&first) {
+      layout.sides = Some((first.left, first.right));
+      let widest = |side: fn(&Margins) -> Option<f64>, lines_up: &dyn Fn(&Cell) -> bool| {
+        self
+          .cells
+          .iter()
+          .zip(&margins)
+          .filter(|(other, _)| lines_up(other))
+          .filter_map(|(_, margins)| side(margins))
+          .reduce(f64::max)
+      };
+      layout.min_margins = Margins {
+        left:   widest(|m| m.left, &|other| other.columns.start == cell.columns.start),
+        right:  widest(|m| m.right, &|other| other.columns.end == cell.columns.end),
+        top:    widest(|m| m.top, &|other| other.rows.start == cell.rows.start),
+        bottom: widest(|m| m.bottom, &|other| other.rows.end == cell.rows.end),
+      };
+    }
+    self
+      .cells
+      .iter()
+      .zip(bounds)
+      .zip(layouts)
+      .map(|((cell, bounds), layout)| {
+        let at = self.cell_bounds(outer, &cell.rows, &cell.columns);
+        cell.plot.layout(render, at, bounds, layout)
+      })
+      .collect()
+  }
+
+  /// The surface below the suptitle, which the grid is laid out in.
+  pub(crate) fn grid_bounds(&self, render: &mut Render) -> Bounds {
+    let mut outer = render.bounds();
+    if self.title.is_some() {
+      outer.y.max +=
+        TITLE_EDGE + title_height(render, self.title.as_ref(), TITLE_SIZE, FontWeight::BOLD);
+    }
+    outer
+  }
+}
+
+/// Whether two ranges of rows (or columns) share any.
+fn overlaps(a: &std::ops::Range<usize>, b: &std::ops::Range<usize>) -> bool {
+  a.start < b.end && b.start < a.end
+}
+
+/// Merges the ranges of a shared axis, or `None` if there are none. Fails if
+/// their units can't be combined.
+fn shared_range<'a>(
+  mut ranges: impl Iterator<Item = DataRange<'a>>,
+  axis: &str,
+) -> PolarsResult<Option<DataRange<'a>>> {
+  let Some(first) = ranges.next() else { return Ok(None) };
+  ranges
+    .try_fold(first, Plot::union_range)
+    .map(Some)
+    .ok_or_else(|| polars_err!(ComputeError: "the shared {axis} axes have incompatible units"))
+}
+
+impl Draw for Figure<'_> {
+  fn draw(&self, render: &mut Render, cursor: Option<Point>) {
+    const TEXT_COLOR: Brush = Brush::Solid(Color::from_rgb8(32, 32, 32));
+
+    if let Some(title) = &self.title {
+      let outer = render.bounds();
+      render.draw_text(DrawText {
+        text: title,
+        size: TITLE_SIZE,
+        weight: FontWeight::BOLD,
+        brush: TEXT_COLOR,
+        position: Point { x: outer.x.center(), y: outer.y.max + TITLE_EDGE },
+        horizontal_align: Align::Center,
+        vertical_align: Align::Start,
+        ..Default::default()
+      });
+    }
+
+    let outer = self.grid_bounds(render);
+    let bounds = self.plot_bounds();
+    let layouts = self.cell_layouts(render, outer, &bounds);
+    for ((cell, bounds), layout) in self.cells.iter().zip(&bounds).zip(&layouts) {
+      let at = self.cell_bounds(outer, &cell.rows, &cell.columns);
+      cell.plot.draw_in(render, at, bounds, layout);
+      if let Some(cursor) = cursor
+        && at.x.contains(&cursor.x)
+        && at.y.contains(&cursor.y)
+      {
+        cell.plot.draw_cursor_in(render, at, bounds, layout, cursor);
+      }
+    }
+  }
+
+  fn image_size(&self) -> (u32, u32) {
+    // Each cell gets as many pixels as a single plot's image, until the whole
+    // image would be too large for a texture.
+    let scale = 2048.0_f64.min(8192.0 / self.rows.max(self.columns) as f64);
+    ((scale * self.columns as f64) as u32, (scale * self.rows as f64) as u32)
+  }
+}
@@ -0,0 +1,95 @@
+//! Built-in tick label formatters, for use with [`Axis::format_ticks`].
+//!
+//! [`Axis::format_ticks`]: crate::Axis::format_ticks
+
+/// Formats fractions as percentages, so `0.25` becomes `25%`.
+pub fn percent(decimals: usize) -> impl Fn(f64) -> String {
+  move |v| format!("{:.*}%", decimals, round(v * 100.0, decimals))
+}
+
+/// Formats values as money with thousands separators, such as `$1,234.50`.
+pub fn currency(symbol: impl Into<String>, decimals: usize) -> impl Fn(f64) -> String {
+  let symbol = symbol.into();
+  move |v| {
+    let v = round(v, decimals);
+    let sign = if v < 0.0 { "-" } else { "" };
+    format!("{sign}{symbol}{}", group_thousands(v.abs(), decimals))
+  }
+}
+
+/// Formats values with thousands separators, such as `1,234,567`.
+pub fn thousands(decimals: usize) -> impl Fn(f64) -> String {
+  move |v| {
+    let v = round(v, decimals);
+    let sign = if v < 0.0 { "-" } else { "" };
+    format!("{sign}{}", group_thousands(v.abs(), decimals))
+  }
+}
+
+/// Formats values with SI prefixes, such as `1.5k` or `20M`.
+pub fn si(decimals: usize) -> impl Fn(f64) -> String {
+  const PREFIXES: [&str; 11] = ["p", "n", "µ", "m", "", "k", "M", "G", "T", "P", "E"];
+
+  move |v| {
+    if v == 0.0 || !v.is_finite() {
+      return format!("{v:.*}", decimals);
+    }
+    let group = (engineering_exponent(v, decimals) / 3).clamp(-4, 6);
+    let mantissa = v / 10_f64.powi(group * 3);
+    format!("{:.*}{}", decimals, mantissa, PREFIXES[(group + 4) as usize])
+  }
+}
+
+/// Formats values in scientific notation, such as `1.23e4`.
+pub fn scientific(decimals: usize) -> impl Fn(f64) -> String {
+  move |v| format!("{:.*e}", decimals, v)
+}
+
+/// Formats values in engineering notation, where the exponent is always a
+/// multiple of three, such as `12.3e3`.
+pub fn engineering(decimals: usize) -> impl Fn(f64) -> String {
+  move |v| {
+    if v == 0.0 || !v.is_finite() {
+      return format!("{v:.*}", decimals);
+    }
+    let exponent = engineering_exponent(v, decimals);
+    format!("{:.*}e{exponent}", decimals, v / 10_f64.powi(exponent))
+  }
+}
+
+/// The exponent, rounded down to a multiple of three, of `v`. It is picked
+/// after rounding to `decimals`, so `999.99` to one decimal is `1.0e3` rather
+/// than `1000.0e0`.
+fn engineering_exponent(v: f64, decimals: usize) -> i32 {
+  let exponent = v.abs().log10().floor() as i32;
+  let exponent = exponent - exponent.rem_euclid(3);
+  if round(v / 10_f64.powi(exponent), decimals).abs() >= 1000.0 { exponent + 3 } else { exponent }
+}
+
+/// `v` rounded to `decimals` places, the same way it is formatted. Values that
+/// round to zero are positive zero, so they don't print with a minus sign.
+fn round(v: f64, decimals: usize) -> f64 {
+  let rounded = format!("{v:.decimals$}").parse::<f64>().unwrap_or(v);
+  if rounded == 0.0 { 0.0 } else { rounded }
+}
+
+fn group_thousands(v: f64, decimals: usize) -> String {
+  let formatted = format!("{v:.decimals$}");
+  let (int, frac) = match formatted.split_once('.') {
+    Some((int, frac)) => (int, Some(frac)),
+    None => (formatted.as_str(), None),
+  };
+
+  let mut out = String::new();
+  for (i, c) in int.chars().enumerate() {
+    if i != 0 && (int.len() - i) % 3 == 0 {
+      out.push(',');
+    }
+    out.push(c);
+  }
+  if let Some(frac) = frac {
+    out.push('.');
+    out.push_str(frac);
+  }
+  out
+}
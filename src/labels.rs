@@ -0,0 +1,241 @@
+use std::f64::consts::SQRT_2;
+
+use kurbo::{Affine, Line, Point};
+use parley::Layout;
+use peniko::{Brush, Color};
+
+use crate::{
+  Axis, Bounds, TickDirection, ViewportTransform,
+  render::{Align, DrawText, Render},
+};
+
+/// The gap kept between neighbouring tick labels.
+const PADDING: f64 = 6.0;
+/// Long labels are only wrapped if they fit in this many lines.
+const MAX_LINES: usize = 3;
+
+/// The side of the viewport an axis is drawn on.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Side {
+  Left,
+  Right,
+  Bottom,
+  Top,
+}
+
+impl Side {
+  /// A tick of the given length at screen position `v`, pointing outwards
+  /// from the viewport.
+  pub(crate) fn tick_line(self, viewport: Bounds, v: f64, length: f64) -> Line {
+    match self {
+      Side::Left => Line::new((viewport.x.min, v), (viewport.x.min - length, v)),
+      Side::Right => Line::new((viewport.x.max, v), (viewport.x.max + length, v)),
+      Side::Bottom => Line::new((v, viewport.y.min), (v, viewport.y.min + length)),
+      Side::Top => Line::new((v, viewport.y.max), (v, viewport.y.max - length)),
+    }
+  }
+
+  /// A tick at screen position `v`, pointing the given way from the axis line.
+  pub(crate) fn tick(
+    self,
+    viewport: Bounds,
+    v: f64,
+    direction: TickDirection,
+    length: f64,
+  ) -> Line {
+    match direction {
+      TickDirection::Out => self.tick_line(viewport, v, length),
+      TickDirection::In => self.tick_line(viewport, v, -length),
+      TickDirection::Both => {
+        Line::new(self.tick_line(viewport, v, -length).p1, self.tick_line(viewport, v, length).p1)
+      }
+    }
+  }
+
+  /// A gridline across the viewport at screen position `v`.
+  pub(crate) fn grid_line(self, viewport: Bounds, v: f64) -> Line {
+    match self {
+      Side::Left | Side::Right => Line::new((viewport.x.min, v), (viewport.x.max, v)),
+      Side::Bottom | Side::Top => Line::new((v, viewport.y.min), (v, viewport.y.max)),
+    }
+  }
+
+  /// The axis line along this side, from `from` to `to` along the axis.
+  pub(crate) fn spine(self, viewport: Bounds, from: f64, to: f64) -> Line {
+    match self {
+      Side::Left => Line::new((viewport.x.min, from), (viewport.x.min, to)),
+      Side::Right => Line::new((viewport.x.max, from), (viewport.x.max, to)),
+      Side::Bottom => Line::new((from, viewport.y.min), (to, viewport.y.min)),
+      Side::Top => Line::new((from, viewport.y.max), (to, viewport.y.max)),
+    }
+  }
+
+  /// A short diagonal slash across the axis line, marking one end of a break.
+  pub(crate) fn break_mark(self, viewport: Bounds, at: f64) -> Line {
+    let center = match self {
+      Side::Left => Point::new(viewport.x.min, at),
+      Side::Right => Point::new(viewport.x.max, at),
+      Side::Bottom => Point::new(at, viewport.y.min),
+      Side::Top => Point::new(at, viewport.y.max),
+    };
+    let half =
+      if self.is_vertical() { kurbo::Vec2::new(8.0, -4.0) } else { kurbo::Vec2::new(4.0, -8.0) };
+    Line::new(center - half, center + half)
+  }
+
+  pub(crate) fn is_vertical(self) -> bool { matches!(self, Side::Left | Side::Right) }
+
+  /// The screen position of `value` along this side, if it lands inside the
+  /// viewport.
+  pub(crate) fn project(
+    self,
+    transform: &ViewportTransform,
+    viewport: Bounds,
+    value: f64,
+  ) -> Option<f64> {
+    if self.is_vertical() {
+      Some((transform * Point::new(0.0, value)).y).filter(|y| viewport.y.contains(y))
+    } else {
+      Some((transform * Point::new(value, 0.0)).x).filter(|x| viewport.x.contains(x))
+    }
+  }
+}
+
+impl Axis {
+  /// Draws tick labels anchored at the given points. Labels that would overlap
+  /// are wrapped or rotated (only on horizontal axes), and then thinned out
+  /// until they fit.
+  pub(crate) fn draw_tick_labels(
+    &self,
+    render: &mut Render,
+    side: Side,
+    labels: Vec<(String, Point)>,
+  ) {
+    let (layouts, angle) = self.layout_tick_labels(render, side, &labels);
+    let stride = stride(side, &layouts, angle, spacing(side, &labels));
+
+    let (horizontal_align, vertical_align) = match (side, angle) {
+      (Side::Bottom, 0.0) => (Align::Center, Align::Start),
+      (Side::Top, 0.0) => (Align::Center, Align::End),
+      (Side::Left, 90.0) => (Align::Center, Align::End),
+      (Side::Right, 90.0) => (Align::Center, Align::Start),
+      (Side::Left | Side::Bottom, _) => (Align::End, Align::Center),
+      (Side::Right | Side::Top, _) => (Align::Start, Align::Center),
+    };
+    for (layout, (_, position)) in layouts.into_iter().zip(labels).step_by(stride) {
+      render.draw_text_layout(
+        layout,
+        DrawText {
+          position,
+          transform: Affine::rotate(-angle.to_radians()),
+          horizontal_align,
+          vertical_align,
+          ..Default::default()
+        },
+      );
+    }
+  }
+
+  /// How far the tick labels reach out from their anchors, away from the
+  /// viewport, once they are wrapped or rotated to fit.
+  pub(crate) fn tick_label_extent(
+    &self,
+    render: &mut Render,
+    side: Side,
+    labels: &[(String, Point)],
+  ) -> f64 {
+    let (layouts, angle) = self.layout_tick_labels(render, side, labels);
+    let (width, height) = max_size(&layouts);
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let (sin, cos) = (sin.abs(), cos.abs());
+    if side.is_vertical() { width * cos + height * sin } else { width * sin + height * cos }
+  }
+
+  /// Lays out each tick label, and picks the angle (in degrees) they are
+  /// drawn at.
+  pub(crate) fn layout_tick_labels(
+    &self,
+    render: &mut Render,
+    side: Side,
+    labels: &[(String, Point)],
+  ) -> (Vec<Layout<Brush>>, f64) {
+    const TEXT_COLOR: Brush = Brush::Solid(Color::from_rgb8(32, 32, 32));
+
+    let spacing = spacing(side, labels);
+    let line_align = match side {
+      Side::Left => Align::End,
+      Side::Right => Align::Start,
+      Side::Bottom | Side::Top => Align::Center,
+    };
+    let mut layout_all = |max_width: Option<f32>| {
+      labels
+        .iter()
+        .map(|(text, _)| {
+          render.layout_text(&DrawText {
+            text,
+            size: 12.0,
+            brush: TEXT_COLOR,
+            horizontal_align: line_align,
+            max_width,
+            ..Default::default()
+          })
+        })
+        .collect::<Vec<_>>()
+    };
+
+    let mut layouts = layout_all(None);
+    let angle = match self.rotation {
+      Some(angle) => angle,
+      None if side.is_vertical() => 0.0,
+      None => {
+        let (width, height) = max_size(&layouts);
+        let wrap_width = (spacing - PADDING) as f32;
+        let can_wrap = wrap_width > 0.0 && labels.iter().any(|(text, _)| text.contains(' '));
+
+        if width + PADDING <= spacing {
+          0.0
+        } else if can_wrap
+          && let wrapped = layout_all(Some(wrap_width))
+          && wrapped.iter().all(|l| l.width() <= wrap_width && l.len() <= MAX_LINES)
+        {
+          layouts = wrapped;
+          0.0
+        } else if height * SQRT_2 + PADDING <= spacing {
+          45.0
+        } else {
+          90.0
+        }
+      }
+    };
+    (layouts, angle)
+  }
+}
+
+/// How many labels to step over between each one drawn, so that labels laid
+/// out at `angle` and `spacing` apart along `side` don't overlap.
+pub(crate) fn stride(side: Side, layouts: &[Layout<Brush>], angle: f64, spacing: f64) -> usize {
+  // Two labels rotated by `angle` clear each other once they are separated by
+  // either their rotated width or their rotated height.
+  let (width, height) = max_size(layouts);
+  let (sin, cos) = angle.to_radians().sin_cos();
+  let (sin, cos) = (sin.abs(), cos.abs());
+  let needed = if side.is_vertical() {
+    (height / cos).min(width / sin)
+  } else {
+    (width / cos).min(height / sin)
+  };
+  ((needed + PADDING) / spacing).ceil().max(1.0) as usize
+}
+
+/// The smallest distance between neighbouring tick labels along `side`.
+fn spacing(side: Side, labels: &[(String, Point)]) -> f64 {
+  let along = |p: Point| if side.is_vertical() { p.y } else { p.x };
+  labels.windows(2).map(|w| (along(w[1].1) - along(w[0].1)).abs()).fold(f64::INFINITY, f64::min)
+}
+
+/// The width and height of the largest of `layouts`.
+fn max_size(layouts: &[Layout<Brush>]) -> (f64, f64) {
+  layouts
+    .iter()
+    .fold((0.0, 0.0), |(w, h), l| (w.max(f64::from(l.width())), h.max(f64::from(l.height()))))
+}
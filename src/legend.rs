@@ -1,69 +1,137 @@
-use kurbo::{Affine, Point, Rect, RoundedRect, Stroke, Vec2};
+use kurbo::{Affine, Point, Rect, RoundedRect, Size, Stroke, Vec2};
+use parley::Layout;
 use peniko::Brush;
 
 use crate::{
-  Axes, Bounds, LineOptions, Marker, Plot,
+  Axes, Bounds, LineOptions, Marker, Plot, PlotBounds,
   render::{Align, DrawText, Render},
 };
 
+/// The gap between the legend and the edge of the viewport, or whatever else
+/// it is next to.
+const MARGIN: f64 = 20.0;
+/// The gap between a legend outside the viewport and the edge of the plot.
+const EDGE: f64 = 10.0;
+const PADDING: f64 = 10.0;
+const GAP: f64 = 10.0;
+const FONT_SIZE: f64 = 20.0;
+const LINE_HEIGHT: f64 = 20.0;
+const MARKER_WIDTH: f64 = 40.0;
+const PATCH_HEIGHT: f64 = 14.0;
+
+/// Where the legend is drawn.
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum LegendPosition {
+  TopLeft,
+  Top,
+  TopRight,
+  Left,
+  Right,
+  BottomLeft,
+  Bottom,
+  #[default]
+  BottomRight,
+  /// Outside the viewport, to the right of it. The viewport shrinks to make
+  /// room.
+  OutsideRight,
+  /// Outside the viewport, below the x axis. The viewport shrinks to make
+  /// room.
+  Below,
+  /// Puts the top left corner of the legend at the given fractions of the
+  /// way across and up the viewport.
+  At(f64, f64),
+  /// Whichever corner of the viewport covers the fewest data points.
+  Best,
+}
+
 pub struct Legend {
   items: Vec<LegendItem>,
 }
 
 pub struct LegendItem {
-  label:  String,
-  line:   Option<LineOptions>,
-  marker: Option<Marker>,
-  color:  Brush,
+  pub(crate) label:  String,
+  /// Drawn as a line across the swatch, in the line's own color.
+  pub(crate) line:   Option<LineOptions>,
+  pub(crate) marker: Option<Marker>,
+  /// Drawn as a filled swatch, for series that fill an area.
+  pub(crate) patch:  bool,
+  pub(crate) color:  Brush,
 }
 
 impl Plot<'_> {
-  pub(crate) fn draw_legend(&self, render: &mut Render, viewport: Bounds) {
+  /// One legend entry per series, or per hue of a scatter colored by hue. A
+  /// scatter's trendline is drawn through its marker, unless the scatter has
+  /// several hues to share it between.
+  pub(crate) fn legend_items(&self) -> Vec<LegendItem> {
     let mut items = vec![];
     for ax in &self.axes {
       match ax {
         Axes::Scatter(sa) => {
+          let trendline = sa.options.trendline.as_ref().map(|trendline| trendline.line.clone());
           if let Some(keys) = sa.hues() {
             for (i, key) in keys.iter().enumerate() {
               items.push(LegendItem {
                 label:  key.to_string(),
                 line:   None,
                 marker: Some(sa.options.marker),
+                patch:  false,
                 color:  crate::theme::ROCKET.sample(i as f32 / keys.len() as f32).into(),
               });
             }
+            if let Some(line) = trendline {
+              items.push(LegendItem {
+                label:  format!("{} trendline", sa.legend_label()),
+                color:  line.color.clone(),
+                line:   Some(line),
+                marker: None,
+                patch:  false,
+              });
+            }
           } else {
             items.push(LegendItem {
-              label:  "scatter".to_string(),
-              line:   None,
+              label:  sa.legend_label(),
+              line:   trendline,
               marker: Some(sa.options.marker),
+              patch:  false,
               color:  sa.options.color.clone(),
             });
           }
-
-          if let Some(trendline) = &sa.options.trendline {
-            items.push(LegendItem {
-              label: "trendline".to_string(),
-
-              line:   Some(trendline.line.clone()),
-              marker: None,
-
-              color: trendline.line.color.clone(),
-            });
-          }
         }
-        _ => {}
+        Axes::Line(la) => items.push(LegendItem {
+          label:  la.legend_label(),
+          line:   Some(la.options.clone()),
+          marker: None,
+          patch:  false,
+          color:  la.options.color.clone(),
+        }),
+        Axes::Histogram(ha) => items.push(LegendItem {
+          label:  ha.legend_label(),
+          line:   None,
+          marker: None,
+          patch:  true,
+          color:  crate::theme::ROCKET.sample(0.0).into(),
+        }),
+        Axes::BarChart(ba) => items.push(LegendItem {
+          label:  ba.legend_label(),
+          line:   None,
+          marker: None,
+          patch:  true,
+          color:  crate::theme::ROCKET.sample(0.0).into(),
+        }),
       }
     }
 
-    let legend = Legend { items };
+    items
+  }
 
-    const MARGIN: f64 = 20.0;
-    const PADDING: f64 = 10.0;
-    const GAP: f64 = 10.0;
-    const FONT_SIZE: f64 = 20.0;
-    const LINE_HEIGHT: f64 = 20.0;
-    const MARKER_WIDTH: f64 = 40.0;
+  /// Lays out the label of each legend item, along with the size of the whole
+  /// legend, or `None` if there is no legend to draw.
+  fn legend_layout(&self, render: &mut Render) -> Option<(Legend, Vec<Layout<Brush>>, Size)> {
+    self.legend?;
+    let legend = Legend { items: self.legend_items() };
+    if legend.items.is_empty() {
+      return None;
+    }
 
     let mut inner_width = 0.0_f64;
     let mut layouts = vec![];
@@ -76,18 +144,42 @@ impl Plot<'_> {
       };
       let layout = render.layout_text(&text);
       inner_width = inner_width.max(f64::from(layout.width()));
-      layouts.push((layout, text));
+      layouts.push(layout);
     }
 
     inner_width += MARKER_WIDTH;
     let inner_height = legend.items.len() as f64 * LINE_HEIGHT;
+    let size = Size::new(inner_width + PADDING * 2.0 + GAP, inner_height + PADDING * 2.0);
+    Some((legend, layouts, size))
+  }
 
-    let rect = Rect::new(
-      viewport.x.max - inner_width - MARGIN - PADDING * 2.0 - GAP,
-      viewport.y.min - inner_height - MARGIN - PADDING * 2.0,
-      viewport.x.max - MARGIN,
-      viewport.y.min - MARGIN,
-    );
+  /// The extra room the legend needs to the right of and below the viewport,
+  /// when it is drawn outside of it.
+  pub(crate) fn legend_room(&self, render: &mut Render) -> (f64, f64) {
+    match (self.legend, self.legend_layout(render)) {
+      (Some(LegendPosition::OutsideRight), Some((_, _, size))) => (size.width + MARGIN, 0.0),
+      (Some(LegendPosition::Below), Some((_, _, size))) => (0.0, size.height + MARGIN),
+      _ => (0.0, 0.0),
+    }
+  }
+
+  pub(crate) fn draw_legend(
+    &self,
+    render: &mut Render,
+    outer: Bounds,
+    viewport: Bounds,
+    bounds: &PlotBounds,
+  ) {
+    let (Some(position), Some((legend, layouts, size))) = (self.legend, self.legend_layout(render))
+    else {
+      return;
+    };
+    let position = match position {
+      LegendPosition::Best => self.best_legend_corner(size, viewport, bounds),
+      position => position,
+    };
+
+    let rect = legend_rect(position, size, outer, viewport);
     let background = RoundedRect::from_rect(rect, 5.0);
     render.fill(
       &background,
@@ -101,17 +193,25 @@ impl Plot<'_> {
       &Stroke::new(2.0),
     );
 
-    for (i, (layout, mut text)) in layouts.into_iter().enumerate() {
+    for (i, layout) in layouts.into_iter().enumerate() {
       let pos = Point::new(
         rect.x0 + PADDING,
         rect.y0 + i as f64 * LINE_HEIGHT + PADDING + LINE_HEIGHT / 2.0,
       );
 
+      if legend.items[i].patch {
+        render.fill(
+          &Rect::new(0.0, -PATCH_HEIGHT / 2.0, MARKER_WIDTH, PATCH_HEIGHT / 2.0),
+          Affine::translate(pos.to_vec2()),
+          &legend.items[i].color,
+        );
+      }
+
       if let Some(line_opts) = &legend.items[i].line {
         render.stroke(
           &kurbo::Line::new(pos, pos + Vec2::new(MARKER_WIDTH, 0.0)),
           Affine::IDENTITY,
-          &legend.items[i].color,
+          &line_opts.color,
           &line_opts.stroke(),
         );
       }
@@ -124,8 +224,74 @@ impl Plot<'_> {
         );
       }
 
-      text.position = pos + Vec2::new(MARKER_WIDTH + GAP, 0.0);
-      render.draw_text_layout(layout, text);
+      render.draw_text_layout(
+        layout,
+        DrawText {
+          position: pos + Vec2::new(MARKER_WIDTH + GAP, 0.0),
+          vertical_align: Align::Center,
+          ..Default::default()
+        },
+      );
     }
   }
+
+  /// The corner of the viewport where a legend of the given size covers the
+  /// fewest data points. Ties go to the first of top right, top left, bottom
+  /// left and bottom right.
+  pub(crate) fn best_legend_corner(
+    &self,
+    size: Size,
+    viewport: Bounds,
+    bounds: &PlotBounds,
+  ) -> LegendPosition {
+    let points = self
+      .axes
+      .iter()
+      .flat_map(|axes| {
+        let transform = self.viewport_transform(axes.binding(), bounds, viewport);
+        axes.points(&transform).into_iter().map(move |p| &transform * p)
+      })
+      .collect::<Vec<_>>();
+
+    [
+      LegendPosition::TopRight,
+      LegendPosition::TopLeft,
+      LegendPosition::BottomLeft,
+      LegendPosition::BottomRight,
+    ]
+    .into_iter()
+    .min_by_key(|&corner| {
+      let rect = legend_rect(corner, size, viewport, viewport);
+      points.iter().filter(|&&p| rect.contains(p)).count()
+    })
+    .unwrap()
+  }
+}
+
+/// Where a legend of the given size goes, for any position but
+/// [`LegendPosition::Best`].
+fn legend_rect(position: LegendPosition, size: Size, outer: Bounds, viewport: Bounds) -> Rect {
+  let left = viewport.x.min + MARGIN;
+  let right = viewport.x.max - MARGIN - size.width;
+  let top = viewport.y.max + MARGIN;
+  let bottom = viewport.y.min - MARGIN - size.height;
+  let center =
+    Point::new(viewport.x.center() - size.width / 2.0, viewport.y.center() - size.height / 2.0);
+
+  let origin = match position {
+    LegendPosition::TopLeft => Point::new(left, top),
+    LegendPosition::Top => Point::new(center.x, top),
+    LegendPosition::TopRight => Point::new(right, top),
+    LegendPosition::Left => Point::new(left, center.y),
+    LegendPosition::Right => Point::new(right, center.y),
+    LegendPosition::BottomLeft => Point::new(left, bottom),
+    LegendPosition::Bottom => Point::new(center.x, bottom),
+    LegendPosition::BottomRight | LegendPosition::Best => Point::new(right, bottom),
+    LegendPosition::OutsideRight => Point::new(outer.x.max - EDGE - size.width, center.y),
+    LegendPosition::Below => Point::new(center.x, outer.y.min - EDGE - size.height),
+    LegendPosition::At(x, y) => {
+      Point::new(viewport.x.min + x * viewport.width(), viewport.y.min + y * viewport.height())
+    }
+  };
+  Rect::from_origin_size(origin, size)
 }
@@ -1,25 +1,33 @@
-use std::fmt;
+use std::{collections::HashSet, fmt, sync::Arc};
 
 use kurbo::{Affine, Cap, Line, Point, Stroke};
 use parley::FontWeight;
 use peniko::{Brush, Color};
-use polars::prelude::{AnyValue, Column};
+use polars::prelude::{AnyValue, PolarsResult, polars_err};
 
 use crate::{
-  bounds::{DataBounds, DataRange, RangeUnit, ViewportTransform},
+  axes::AxisBinding,
+  bounds::{DataRange, RangeUnit},
+  labels::Side,
   render::{Align, DrawText, Render},
 };
 
 mod axes;
 mod bounds;
+mod calendar;
+mod figure;
+mod labels;
 mod legend;
 mod marker;
 mod render;
 
+pub mod format;
 pub mod theme;
 
 pub use axes::*;
-pub use bounds::{Bounds, Range};
+pub use bounds::{Bounds, Range, ViewportTransform};
+pub use figure::Figure;
+pub use legend::LegendPosition;
 pub use marker::Marker;
 
 pub(crate) trait ResultExt<T> {
@@ -41,10 +49,17 @@ impl<T> ResultExt<T> for polars::prelude::PolarsResult<T> {
 pub struct Plot<'a> {
   pub x: Axis,
   pub y: Axis,
+  x2:    Option<Axis>,
+  y2:    Option<Axis>,
 
   border: Option<StrokeStyle>,
+  spines: Spines,
   grid:   Option<StrokeStyle>,
   title:  Option<String>,
+  legend: Option<LegendPosition>,
+
+  equal_aspect: bool,
+  margins:      Margins,
 
   axes: Vec<Axes<'a>>,
 }
@@ -55,19 +70,126 @@ pub struct StrokeStyle {
 }
 
 pub struct Axis {
-  title:  Option<String>,
-  scale:  Scale,
-  min:    Option<f64>,
-  max:    Option<f64>,
-  margin: f64,
-  ticks:  Ticks,
+  title:       Option<String>,
+  scale:       Scale,
+  min:         Option<f64>,
+  max:         Option<f64>,
+  margin:      f64,
+  ticks:       Ticks,
+  minor:       Option<MinorTicks>,
+  power_style: PowerStyle,
+  inverted:    bool,
+  format:      Option<Box<dyn Fn(f64) -> String>>,
+  max_ticks:   Option<u32>,
+  rotation:    Option<f64>,
+  breaks:      Vec<Range>,
+  categories:  Option<Vec<String>>,
+  spine:       Spine,
+}
+
+pub struct MinorTicks {
+  subdivisions: Option<usize>,
+  length:       f64,
+  stroke:       StrokeStyle,
+  grid:         Option<StrokeStyle>,
+}
+
+/// The axis line of an [`Axis`], along with its major ticks.
+pub struct Spine {
+  position:       SpinePosition,
+  stroke:         Option<StrokeStyle>,
+  tick_direction: TickDirection,
+  tick_length:    f64,
+}
+
+/// Where an axis line is placed, across the plot.
+#[derive(Default, Clone, Copy, PartialEq)]
+pub enum SpinePosition {
+  /// Along the edge of the plot.
+  #[default]
+  Edge,
+  /// Where the other axis reads zero, such as for centered axes.
+  Zero,
+  /// Where the other axis reads this value, or the nearest edge of the plot
+  /// if that is off it.
+  Value(f64),
+}
+
+/// Which way ticks point from their axis line.
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub enum TickDirection {
+  /// Away from the plot.
+  #[default]
+  Out,
+  /// Into the plot.
+  In,
+  /// Across the axis line, both ways.
+  Both,
+}
+
+/// The space between the edge of a plot and its viewport, on each side. Sides
+/// left unset are sized to fit what is drawn there.
+#[derive(Default, Clone, Copy)]
+pub struct Margins {
+  left:   Option<f64>,
+  right:  Option<f64>,
+  top:    Option<f64>,
+  bottom: Option<f64>,
 }
 
+/// Which sides of the plot get an axis line.
 #[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub enum Spines {
+  /// All four sides.
+  All,
+  /// Only the left and bottom, plus the right and top when there are
+  /// secondary axes.
+  #[default]
+  LeftBottom,
+  /// No axis lines, although ticks are still drawn.
+  None,
+}
+
+#[derive(Default, Clone)]
 pub enum Scale {
   #[default]
   Linear,
-  Logarithmic,
+  Logarithmic {
+    base: f64,
+  },
+  /// Linear within `linthresh` of zero, and logarithmic beyond it, on both
+  /// sides of zero.
+  SymLog {
+    linthresh: f64,
+  },
+  /// A user-defined scale. See [`ScaleFn`].
+  Custom(Arc<dyn ScaleFn>),
+}
+
+/// A user-defined axis scale, such as logit or sqrt.
+pub trait ScaleFn {
+  /// Maps a data value into a space that is linear on screen.
+  fn forward(&self, value: f64) -> f64;
+
+  /// The inverse of [`ScaleFn::forward`].
+  fn inverse(&self, value: f64) -> f64;
+
+  /// Suggests tick positions, in data space, for the given data range. The
+  /// default of `None` uses the same ticks as a linear axis.
+  fn ticks(&self, range: Range, count: u32) -> Option<Vec<f64>> {
+    let _ = (range, count);
+    None
+  }
+}
+
+/// How ticks on a logarithmic axis are labeled.
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub enum PowerStyle {
+  /// Labels read as powers of the base, such as `10^3` or `2^10`.
+  #[default]
+  Exponent,
+  /// Labels use SI prefixes, such as `1k` or `100M`. Only applies to base 10.
+  Prefix,
 }
 
 #[derive(Default)]
@@ -75,17 +197,29 @@ pub enum Ticks {
   #[default]
   Auto,
   Fixed(usize),
+  /// Ticks at exactly these values. Ticks without a label are formatted like
+  /// any other tick on the axis.
+  Manual(Vec<(f64, Option<String>)>),
 }
 
 impl Default for Axis {
   fn default() -> Self {
     Axis {
-      title:  None,
-      scale:  Scale::Linear,
-      min:    None,
-      max:    None,
-      margin: 0.1,
-      ticks:  Ticks::Auto,
+      title:       None,
+      scale:       Scale::Linear,
+      min:         None,
+      max:         None,
+      margin:      0.1,
+      ticks:       Ticks::Auto,
+      minor:       None,
+      power_style: PowerStyle::Exponent,
+      inverted:    false,
+      format:      None,
+      max_ticks:   None,
+      rotation:    None,
+      breaks:      vec![],
+      categories:  None,
+      spine:       Spine::default(),
     }
   }
 }
@@ -95,10 +229,17 @@ impl<'a> Plot<'a> {
     Plot {
       x:      Axis::default(),
       y:      Axis::default(),
+      x2:     None,
+      y2:     None,
       border: Some(StrokeStyle::new(1.0)),
+      spines: Spines::LeftBottom,
       grid:   None,
       title:  None,
-      axes:   Vec::new(),
+      legend: Some(LegendPosition::default()),
+
+      equal_aspect: false,
+      margins:      Margins::default(),
+      axes:         Vec::new(),
     }
   }
 
@@ -107,50 +248,145 @@ impl<'a> Plot<'a> {
     self
   }
 
+  /// Removes the border. Axis lines styled with [`Spine::stroke`] are still
+  /// drawn.
   pub fn no_border(&mut self) { self.border = None; }
 
+  /// Moves the legend. By default, it is inside the bottom right corner.
+  pub fn legend(&mut self, position: LegendPosition) -> &mut Self {
+    self.legend = Some(position);
+    self
+  }
+
+  pub fn no_legend(&mut self) { self.legend = None; }
+
   pub fn border(&mut self) -> &mut StrokeStyle {
     self.border = Some(StrokeStyle::new(1.0));
     self.border.as_mut().unwrap()
   }
 
+  /// Picks which sides of the plot get an axis line. Each line is styled by
+  /// [`Plot::border`], unless its axis overrides it with [`Spine::stroke`].
+  pub fn spines(&mut self, spines: Spines) -> &mut Self {
+    self.spines = spines;
+    self
+  }
+
   pub fn grid(&mut self) -> &mut StrokeStyle {
     self.grid = Some(StrokeStyle::new(1.0));
     self.grid.as_mut().unwrap()
   }
 
-  fn bounds(&self) -> DataBounds<'_> {
-    let mut bounds: Option<DataBounds> = None;
+  /// Scales both axes the same, so one unit on x is as long on screen as one
+  /// unit on y. The plot shrinks along one side to fit, and stays centered.
+  pub fn equal_aspect(&mut self) -> &mut Self {
+    self.equal_aspect = true;
+    self
+  }
+
+  /// Overrides the space left around the plot on some sides. Other sides are
+  /// sized to fit their ticks, tick labels and titles.
+  pub fn margins(&mut self) -> &mut Margins { &mut self.margins }
+
+  /// Adds a secondary x axis along the top of the plot. Series are drawn
+  /// against it with `on_x2`.
+  pub fn x2(&mut self) -> &mut Axis { self.x2.get_or_insert_with(Axis::default) }
+
+  /// Adds a secondary y axis along the right of the plot. Series are drawn
+  /// against it with `on_y2`.
+  pub fn y2(&mut self) -> &mut Axis { self.y2.get_or_insert_with(Axis::default) }
+
+  fn x_axis(&self, secondary: bool) -> &Axis {
+    if secondary { self.x2.as_ref().unwrap_or(&self.x) } else { &self.x }
+  }
+
+  fn y_axis(&self, secondary: bool) -> &Axis {
+    if secondary { self.y2.as_ref().unwrap_or(&self.y) } else { &self.y }
+  }
+
+  /// Checks that every series can share its axes with the others. Values
+  /// along an axis need compatible units: numbers, dates and datetimes, or
+  /// durations. A series bound to a secondary axis also needs the plot to
+  /// have that axis. [`Plot::save`] and [`Plot::show`] fail with this error,
+  /// and otherwise a series that fails it is left out of the axis ranges, or
+  /// drawn against the primary axis instead of the missing one.
+  pub fn check(&self) -> PolarsResult<()> { self.union_bounds().1 }
+
+  /// The data range along each axis, covering the series drawn against it.
+  /// Series bound to a secondary axis that doesn't exist use the primary axis.
+  fn bounds(&self) -> PlotBounds<'_> {
+    let (bounds, result) = self.union_bounds();
+    result.log_err();
+    bounds
+  }
+
+  /// The bounds of every series with compatible units, along with an error for
+  /// the first series that isn't.
+  fn union_bounds(&self) -> (PlotBounds<'_>, PolarsResult<()>) {
+    const AXIS_NAMES: [&str; 4] = ["x", "y", "x2", "y2"];
+    const EMPTY: DataRange<'static> = DataRange::Continuous {
+      range:      Range::new(0.0, 1.0),
+      unit:       RangeUnit::Absolute,
+      margin_min: false,
+      margin_max: false,
+    };
+
+    let mut ranges: [Option<DataRange>; 4] = Default::default();
+    let mut result = Ok(());
     for axes in &self.axes {
       let Some(bound) = axes.data_bounds().log_err() else { continue };
-      bounds = Some(match bounds {
-        Some(b) => self.union_bounds(b, bound),
-        None => bound,
+      let binding = axes.binding();
+      let x = if binding.x2 && self.x2.is_some() { 2 } else { 0 };
+      let y = if binding.y2 && self.y2.is_some() { 3 } else { 1 };
+      for (bound, axis, name) in [(binding.x2, x, "x2"), (binding.y2, y, "y2")] {
+        if bound && axis < 2 {
+          result = result.and(Err(polars_err!(
+            ComputeError: "{} is drawn against the {name} axis, which the plot doesn't have",
+            axes.describe(),
+          )));
+        }
+      }
+
+      let mut merged = ranges.clone();
+      let merge = [(x, bound.x), (y, bound.y)].into_iter().try_for_each(|(i, range)| {
+        merged[i] = Some(match merged[i].take() {
+          Some(r) => {
+            let (axis_values, values) = (r.describe(), range.describe());
+            Self::union_range(r, range).ok_or_else(|| {
+              polars_err!(
+                ComputeError: "{} has {} along the {} axis, which already has {}",
+                axes.describe(), values, AXIS_NAMES[i], axis_values,
+              )
+            })?
+          }
+          None => range,
+        });
+        PolarsResult::Ok(())
       });
+      match merge {
+        Ok(()) => ranges = merged,
+        Err(e) => result = result.and(Err(e)),
+      }
     }
 
-    bounds.unwrap_or(DataBounds {
-      x: DataRange::Continuous {
-        range:      Range::new(0.0, 1.0),
-        unit:       RangeUnit::Absolute,
-        margin_min: false,
-        margin_max: false,
-      },
-      y: DataRange::Continuous {
-        range:      Range::new(0.0, 1.0),
-        unit:       RangeUnit::Absolute,
-        margin_min: false,
-        margin_max: false,
-      },
-    })
+    let [x, y, x2, y2] = ranges;
+    let x = x.or_else(|| x2.clone()).unwrap_or(EMPTY);
+    let y = y.or_else(|| y2.clone()).unwrap_or(EMPTY);
+    let bounds = PlotBounds {
+      x2: self.x2.as_ref().map(|axis| axis.ordered(x2.unwrap_or_else(|| x.clone()))),
+      y2: self.y2.as_ref().map(|axis| axis.ordered(y2.unwrap_or_else(|| y.clone()))),
+      x:  self.x.ordered(x),
+      y:  self.y.ordered(y),
+    };
+    (bounds, result)
   }
 
-  fn union_bounds(&self, a: DataBounds<'_>, b: DataBounds<'_>) -> DataBounds<'_> {
-    DataBounds { x: self.union_range(a.x, b.x), y: self.union_range(a.y, b.y) }
-  }
-
-  fn union_range(&self, a: DataRange<'_>, b: DataRange<'_>) -> DataRange<'_> {
-    match (a, b) {
+  /// Merges two data ranges along the same axis. Categories are merged by
+  /// name, in the order they are first seen, and continuous values on a
+  /// categorical axis are treated as category positions. Continuous ranges
+  /// are converted into a common unit, or `None` if they have none.
+  fn union_range<'b>(a: DataRange<'b>, b: DataRange<'b>) -> Option<DataRange<'b>> {
+    Some(match (a, b) {
       (
         DataRange::Continuous {
           range: range_a,
@@ -158,30 +394,129 @@ impl<'a> Plot<'a> {
           margin_min: min_a,
           margin_max: max_a,
         },
-        DataRange::Continuous { range: range_b, unit: _, margin_min: min_b, margin_max: max_b },
-      ) => DataRange::Continuous {
-        range:      range_a.union(range_b),
-        unit:       unit_a, // TODO
-        margin_min: min_a || min_b,
-        margin_max: max_a || max_b,
-      },
-      _ => panic!("Cannot union non-continuous ranges"),
-    }
+        DataRange::Continuous {
+          range: range_b,
+          unit: unit_b,
+          margin_min: min_b,
+          margin_max: max_b,
+        },
+      ) => {
+        let unit = calendar::common_unit(unit_a, unit_b)?;
+        let convert = |range: Range, from| range.map(|v| calendar::convert(v, from, unit));
+        DataRange::Continuous {
+          range: convert(range_a, unit_a).union(convert(range_b, unit_b)),
+          unit,
+          margin_min: min_a || min_b,
+          margin_max: max_a || max_b,
+        }
+      }
+      (DataRange::Categorical(mut a), DataRange::Categorical(b)) => {
+        let mut seen = a.iter().map(bounds::category_name).collect::<HashSet<_>>();
+        a.extend(b.into_iter().filter(|v| seen.insert(bounds::category_name(v))));
+        DataRange::Categorical(a)
+      }
+      // Plain numbers can sit on a category axis, at the category positions,
+      // but dates and durations can't.
+      (
+        categories @ DataRange::Categorical(_),
+        DataRange::Continuous { unit: RangeUnit::Absolute, .. },
+      )
+      | (
+        DataRange::Continuous { unit: RangeUnit::Absolute, .. },
+        categories @ DataRange::Categorical(_),
+      ) => categories,
+      (DataRange::Categorical(_), DataRange::Continuous { .. })
+      | (DataRange::Continuous { .. }, DataRange::Categorical(_)) => return None,
+    })
   }
 
-  fn pretty_bounds(&self, data_bounds: DataBounds<'_>) -> Bounds {
-    Bounds { x: self.x.pretty_range(data_bounds.x), y: self.y.pretty_range(data_bounds.y) }
+  fn viewport_transform(
+    &self,
+    binding: AxisBinding,
+    bounds: &PlotBounds,
+    viewport: Bounds,
+  ) -> ViewportTransform {
+    let x = self.x_axis(binding.x2);
+    let y = self.y_axis(binding.y2);
+    let from =
+      Bounds::new(x.scaled_range(bounds.x(binding.x2)), y.scaled_range(bounds.y(binding.y2)));
+
+    ViewportTransform {
+      affine:   from.transform_to(viewport),
+      x:        x.scale.clone(),
+      y:        y.scale.clone(),
+      x_breaks: x.scaled_breaks(bounds.x(binding.x2)),
+      y_breaks: y.scaled_breaks(bounds.y(binding.y2)),
+
+      x_categories: bounds.x(binding.x2).category_positions(),
+      y_categories: bounds.y(binding.y2).category_positions(),
+      x_unit:       bounds.x(binding.x2).unit(),
+      y_unit:       bounds.y(binding.y2).unit(),
+    }
   }
+}
 
-  fn viewport_transform(&self, data_bounds: DataBounds<'_>, viewport: Bounds) -> ViewportTransform {
-    let pretty = self.pretty_bounds(data_bounds);
-    let from = Bounds::new(
-      pretty.x.map(|v| self.x.scale.scale_value(v)),
-      pretty.y.map(|v| self.y.scale.scale_value(v)),
-    );
-    let affine = from.transform_to(viewport);
+/// Tick labels to leave off, for a plot whose neighbour in a [`Figure`] shows
+/// the same ones.
+#[derive(Clone, Copy, Default)]
+struct HiddenLabels {
+  x: bool,
+  y: bool,
+}
+
+/// How a plot fits in with its neighbours, as one cell of a [`Figure`].
+#[derive(Clone, Copy, Default)]
+struct CellLayout {
+  hidden:      HiddenLabels,
+  /// The smallest margin on each side, so that the viewports of plots in the
+  /// same row or column line up.
+  min_margins: Margins,
+  /// How far the left and right axes reach, if an earlier pass has already
+  /// measured them. Unlike the top and bottom, they don't depend on the
+  /// margins, so they can be reused rather than measured again.
+  sides:       Option<(f64, f64)>,
+}
+
+/// Where a plot's viewport goes, and how far its ticks and tick labels reach
+/// out from each side of it. Measuring the labels lays out all of their text,
+/// so this is worked out once per frame and shared by everything drawn in it.
+struct PlotLayout {
+  viewport: Bounds,
+  hidden:   HiddenLabels,
+  left:     f64,
+  right:    f64,
+  top:      f64,
+  bottom:   f64,
+  /// The space left on each side, before any letterboxing.
+  margins:  Margins,
+}
+
+/// The gap between an axis's tick labels and its title.
+const TITLE_GAP: f64 = 8.0;
+
+/// The height of a title plus the gap beside it, or zero if there is no title.
+fn title_height(render: &mut Render, title: Option<&String>, size: f32, weight: FontWeight) -> f64 {
+  title.map_or(0.0, |text| {
+    let layout = render.layout_text(&DrawText { text, size, weight, ..Default::default() });
+    f64::from(layout.height()) + TITLE_GAP
+  })
+}
 
-    ViewportTransform { affine, x: self.x.scale, y: self.y.scale }
+/// The data ranges of every axis on a [`Plot`].
+struct PlotBounds<'a> {
+  x:  DataRange<'a>,
+  y:  DataRange<'a>,
+  x2: Option<DataRange<'a>>,
+  y2: Option<DataRange<'a>>,
+}
+
+impl<'a> PlotBounds<'a> {
+  fn x(&self, secondary: bool) -> &DataRange<'a> {
+    if secondary { self.x2.as_ref().unwrap_or(&self.x) } else { &self.x }
+  }
+
+  fn y(&self, secondary: bool) -> &DataRange<'a> {
+    if secondary { self.y2.as_ref().unwrap_or(&self.y) } else { &self.y }
   }
 }
 
@@ -193,6 +528,11 @@ impl StrokeStyle {
     self
   }
 
+  pub fn color(&mut self, color: impl Into<Brush>) -> &mut Self {
+    self.brush = Some(color.into());
+    self
+  }
+
   pub fn dashed(&mut self) -> &mut Self { self.dash_style(&[4.0]) }
 
   pub fn dash_style(&mut self, dashes: &[f64]) -> &mut Self {
@@ -223,36 +563,249 @@ impl Axis {
     self
   }
 
+  /// Collapses the interval between `a` and `b` down to a small gap, which is
+  /// marked on the axis line. Ticks are picked separately on either side, and
+  /// any data inside the interval is squeezed into the gap.
+  pub fn break_range(&mut self, a: f64, b: f64) -> &mut Self {
+    self.breaks.push(Range::new(a, b).ascending());
+    self.breaks.sort_by(|a, b| a.min.total_cmp(&b.min));
+    self
+  }
+
+  /// Places categories along this axis in the given order, instead of the
+  /// order they are first seen in. Categories that aren't listed follow after,
+  /// and listed ones that no series has still get an empty slot, so that a
+  /// fixed set of categories (such as weekdays) lines up across plots.
+  pub fn categories<S: Into<String>>(&mut self, order: impl IntoIterator<Item = S>) -> &mut Self {
+    self.categories = Some(order.into_iter().map(Into::into).collect());
+    self
+  }
+
+  /// Runs this axis from its maximum to its minimum, so that values increase
+  /// to the left or downwards.
+  pub fn invert(&mut self) -> &mut Self {
+    self.inverted = true;
+    self
+  }
+
+  /// Draws `subdivisions - 1` minor ticks between each pair of major ticks.
+  pub fn minor_ticks(&mut self, subdivisions: usize) -> &mut MinorTicks {
+    self.minor = Some(MinorTicks::new(Some(subdivisions)));
+    self.minor.as_mut().unwrap()
+  }
+
+  /// Draws minor ticks between major ticks, picking the subdivisions from the
+  /// tick step. On logarithmic axes, these land on 2..9 times each decade.
+  pub fn minor_ticks_auto(&mut self) -> &mut MinorTicks {
+    self.minor = Some(MinorTicks::new(None));
+    self.minor.as_mut().unwrap()
+  }
+
+  /// The axis line of this axis, and the style of its major ticks.
+  pub fn spine(&mut self) -> &mut Spine { &mut self.spine }
+
   pub fn ticks_fixed(&mut self, count: usize) -> &mut Self {
     self.ticks = Ticks::Fixed(count);
     self
   }
 
-  pub fn log_scale(&mut self) -> &mut Self {
-    self.scale = Scale::Logarithmic;
+  /// Aims for at most `count` major ticks. Numeric ticks are picked to land
+  /// near this count, and categorical labels are thinned down to it.
+  pub fn max_ticks(&mut self, count: u32) -> &mut Self {
+    self.max_ticks = Some(count);
+    self
+  }
+
+  /// Rotates tick labels counter-clockwise by `degrees`. By default, labels on
+  /// the x axis are rotated only when they would otherwise overlap.
+  pub fn tick_rotation(&mut self, degrees: f64) -> &mut Self {
+    self.rotation = Some(degrees);
+    self
+  }
+
+  /// Places ticks, and their grid lines, at exactly the given values.
+  pub fn ticks_manual(
+    &mut self,
+    ticks: impl IntoIterator<Item = (f64, Option<String>)>,
+  ) -> &mut Self {
+    self.ticks = Ticks::Manual(ticks.into_iter().collect());
+    self
+  }
+
+  pub fn log_scale(&mut self) -> &mut Self { self.log_scale_base(10.0) }
+
+  /// Use a logarithmic scale with the given base, such as `2.0` or
+  /// [`std::f64::consts::E`].
+  ///
+  /// # Panics
+  ///
+  /// Panics if `base` isn't a finite number greater than zero and other than
+  /// one.
+  pub fn log_scale_base(&mut self, base: f64) -> &mut Self {
+    assert!(
+      base > 0.0 && base != 1.0 && base.is_finite(),
+      "log base must be positive, finite and not 1, got {base}",
+    );
+    self.scale = Scale::Logarithmic { base };
+    self
+  }
+
+  /// Sets how ticks on a logarithmic axis are labeled.
+  pub fn power_labels(&mut self, style: PowerStyle) -> &mut Self {
+    self.power_style = style;
+    self
+  }
+
+  /// Formats tick labels with the given function, instead of the default
+  /// labels for this axis. See [`format`] for some built-in formatters.
+  pub fn format_ticks(&mut self, format: impl Fn(f64) -> String + 'static) -> &mut Self {
+    self.format = Some(Box::new(format));
+    self
+  }
+
+  /// Use a symmetric log scale, which is linear within `linthresh` of zero.
+  ///
+  /// # Panics
+  ///
+  /// Panics if `linthresh` isn't a positive, finite number.
+  pub fn symlog_scale(&mut self, linthresh: f64) -> &mut Self {
+    assert!(
+      linthresh > 0.0 && linthresh.is_finite(),
+      "symlog threshold must be positive and finite, got {linthresh}",
+    );
+    self.scale = Scale::SymLog { linthresh };
+    self
+  }
+
+  /// Uses a user-defined scale. Like the function given to
+  /// [`Axis::format_ticks`], it needn't be `Send` or `Sync`, as a plot is only
+  /// ever drawn on the thread that built it.
+  pub fn custom_scale(&mut self, scale: impl ScaleFn + 'static) -> &mut Self {
+    self.scale = Scale::Custom(Arc::new(scale));
     self
   }
 }
 
+impl MinorTicks {
+  fn new(subdivisions: Option<usize>) -> Self {
+    MinorTicks { subdivisions, length: 5.0, stroke: StrokeStyle::new(1.0), grid: None }
+  }
+
+  pub fn length(&mut self, length: f64) -> &mut Self {
+    self.length = length;
+    self
+  }
+
+  pub fn stroke(&mut self) -> &mut StrokeStyle { &mut self.stroke }
+
+  pub fn grid(&mut self) -> &mut StrokeStyle {
+    self.grid = Some(StrokeStyle::new(0.5));
+    self.grid.as_mut().unwrap()
+  }
+}
+
+impl Default for Spine {
+  fn default() -> Self {
+    Spine {
+      position:       SpinePosition::Edge,
+      stroke:         None,
+      tick_direction: TickDirection::Out,
+      tick_length:    10.0,
+    }
+  }
+}
+
+impl Spine {
+  pub fn position(&mut self, position: SpinePosition) -> &mut Self {
+    self.position = position;
+    self
+  }
+
+  /// Moves this axis line to where the other axis reads zero.
+  pub fn at_zero(&mut self) -> &mut Self { self.position(SpinePosition::Zero) }
+
+  /// Styles this axis line and its ticks, instead of using [`Plot::border`].
+  pub fn stroke(&mut self) -> &mut StrokeStyle {
+    self.stroke.get_or_insert_with(|| StrokeStyle::new(1.0))
+  }
+
+  pub fn tick_direction(&mut self, direction: TickDirection) -> &mut Self {
+    self.tick_direction = direction;
+    self
+  }
+
+  pub fn tick_length(&mut self, length: f64) -> &mut Self {
+    self.tick_length = length;
+    self
+  }
+
+  /// The distance from the axis line to the tick labels.
+  fn label_offset(&self) -> f64 {
+    match self.tick_direction {
+      TickDirection::Out | TickDirection::Both => self.tick_length + 5.0,
+      TickDirection::In => 5.0,
+    }
+  }
+}
+
+impl Margins {
+  /// Sets the space left of the viewport, which holds the y axis labels and
+  /// title.
+  pub fn left(&mut self, margin: f64) -> &mut Self {
+    self.left = Some(margin);
+    self
+  }
+
+  /// Sets the space right of the viewport. A legend placed
+  /// [`LegendPosition::OutsideRight`] gets its own room beyond this.
+  pub fn right(&mut self, margin: f64) -> &mut Self {
+    self.right = Some(margin);
+    self
+  }
+
+  /// Sets the space above the viewport, which holds the title.
+  pub fn top(&mut self, margin: f64) -> &mut Self {
+    self.top = Some(margin);
+    self
+  }
+
+  /// Sets the space below the viewport, which holds the x axis labels and
+  /// title. A legend placed [`LegendPosition::Below`] gets its own room
+  /// beyond this.
+  pub fn bottom(&mut self, margin: f64) -> &mut Self {
+    self.bottom = Some(margin);
+    self
+  }
+
+  /// Sets the margin on every side.
+  pub fn all(&mut self, margin: f64) -> &mut Self {
+    self.left(margin).right(margin).top(margin).bottom(margin)
+  }
+}
+
 impl<'a> ScatterAxes<'a> {}
 
 impl Plot<'_> {
-  fn draw(&self, render: &mut Render) {
+  /// Draws the plot, its axes and titles into the `outer` region of the render
+  /// surface, with the data ranges in `bounds`, laid out by `layout`.
+  fn draw_in(&self, render: &mut Render, outer: Bounds, bounds: &PlotBounds, layout: &PlotLayout) {
     const TEXT_COLOR: Brush = Brush::Solid(Color::from_rgb8(32, 32, 32));
-    const LINE_COLOR: Brush = Brush::Solid(Color::from_rgb8(128, 128, 128));
-
-    let outer =
-      Bounds::new(Range::new(0.0, render.size().width), Range::new(render.size().height, 0.0));
 
-    let viewport = outer.shrink(80.0);
+    let viewport = layout.viewport;
+    let center = Point::new(outer.x.center(), outer.y.center());
 
+    let x2_title = self.x2.as_ref().and_then(|x2| x2.title.as_ref());
     if let Some(title) = &self.title {
+      let x2_title_height = title_height(render, x2_title, 20.0, FontWeight::NORMAL);
       render.draw_text(DrawText {
         text: title,
         size: 32.0,
         weight: FontWeight::BOLD,
         brush: TEXT_COLOR,
-        position: Point { x: outer.width() / 2.0, y: viewport.y.max - 10.0 },
+        position: Point {
+          x: center.x,
+          y: viewport.y.max - layout.top - x2_title_height - TITLE_GAP,
+        },
         horizontal_align: Align::Center,
         vertical_align: Align::End,
         ..Default::default()
@@ -263,7 +816,7 @@ impl Plot<'_> {
       render.draw_text(DrawText {
         text: x_label,
         size: 24.0,
-        position: Point { x: outer.width() / 2.0, y: viewport.y.min + 40.0 },
+        position: Point { x: center.x, y: viewport.y.min + layout.bottom + TITLE_GAP },
         brush: TEXT_COLOR,
         horizontal_align: Align::Center,
         vertical_align: Align::Start,
@@ -275,7 +828,7 @@ impl Plot<'_> {
       render.draw_text(DrawText {
         text: y_label,
         size: 24.0,
-        position: Point { x: viewport.x.min - 40.0, y: -outer.height() / 2.0 },
+        position: Point { x: viewport.x.min - layout.left - TITLE_GAP, y: center.y },
         brush: TEXT_COLOR,
         transform: vello::kurbo::Affine::rotate(-std::f64::consts::FRAC_PI_2),
         horizontal_align: Align::Center,
@@ -284,112 +837,400 @@ impl Plot<'_> {
       });
     }
 
-    if let Some(stroke) = &self.border {
-      render.stroke(
-        &Line::new(
-          Point::new(viewport.x.min, viewport.y.min),
-          Point::new(viewport.x.max, viewport.y.min),
-        ),
-        Affine::IDENTITY,
-        stroke.brush.as_ref().unwrap_or(&LINE_COLOR),
-        &stroke.stroke,
-      );
-      render.stroke(
-        &Line::new(
-          Point::new(viewport.x.min, viewport.y.min),
-          Point::new(viewport.x.min, viewport.y.max),
-        ),
-        Affine::IDENTITY,
-        stroke.brush.as_ref().unwrap_or(&LINE_COLOR),
-        &stroke.stroke,
-      );
+    if let Some(x2_label) = x2_title {
+      render.draw_text(DrawText {
+        text: x2_label,
+        size: 20.0,
+        position: Point { x: center.x, y: viewport.y.max - layout.top - TITLE_GAP },
+        brush: TEXT_COLOR,
+        horizontal_align: Align::Center,
+        vertical_align: Align::End,
+        ..Default::default()
+      });
+    }
+
+    if let Some(y2_label) = self.y2.as_ref().and_then(|y2| y2.title.as_ref()) {
+      render.draw_text(DrawText {
+        text: y2_label,
+        size: 24.0,
+        position: Point { x: viewport.x.max + layout.right + TITLE_GAP, y: center.y },
+        brush: TEXT_COLOR,
+        transform: vello::kurbo::Affine::rotate(-std::f64::consts::FRAC_PI_2),
+        horizontal_align: Align::Center,
+        vertical_align: Align::Start,
+        ..Default::default()
+      });
     }
 
-    let tick_stroke = Stroke::new(1.0);
+    let transform = self.viewport_transform(AxisBinding::default(), bounds, viewport);
+    let y2_transform = self
+      .y2
+      .as_ref()
+      .map(|_| self.viewport_transform(AxisBinding { x2: false, y2: true }, bounds, viewport));
+    let x2_transform = self
+      .x2
+      .as_ref()
+      .map(|_| self.viewport_transform(AxisBinding { x2: true, y2: false }, bounds, viewport));
+
+    let sides = [
+      (Side::Left, Some((&self.y, &transform))),
+      (Side::Bottom, Some((&self.x, &transform))),
+      (Side::Right, self.y2.as_ref().zip(y2_transform.as_ref())),
+      (Side::Top, self.x2.as_ref().zip(x2_transform.as_ref())),
+    ];
+    for (side, axis) in sides {
+      let Some(stroke) = self.spine_stroke(axis.map(|(axis, _)| axis)) else { continue };
+      match axis {
+        Some((axis, transform)) => {
+          let at = axis.spine_bounds(side, transform, viewport);
+          self.draw_spine(render, stroke, side, transform, at);
+        }
+        None => self.draw_spine(render, stroke, side, &transform, viewport),
+      }
+    }
 
-    let data_bounds = self.bounds();
-    let transform = self.viewport_transform(data_bounds, viewport);
-    let transform = &transform;
+    let labels = self.draw_axis(render, &self.y, Side::Left, &bounds.y, &transform, viewport);
+    if !layout.hidden.y {
+      self.y.draw_tick_labels(render, Side::Left, labels);
+    }
+    let labels = self.draw_axis(render, &self.x, Side::Bottom, &bounds.x, &transform, viewport);
+    if !layout.hidden.x {
+      self.x.draw_tick_labels(render, Side::Bottom, labels);
+    }
+    if let (Some(y2), Some(range), Some(transform)) = (&self.y2, &bounds.y2, &y2_transform) {
+      let labels = self.draw_axis(render, y2, Side::Right, range, transform, viewport);
+      y2.draw_tick_labels(render, Side::Right, labels);
+    }
+    if let (Some(x2), Some(range), Some(transform)) = (&self.x2, &bounds.x2, &x2_transform) {
+      let labels = self.draw_axis(render, x2, Side::Top, range, transform, viewport);
+      x2.draw_tick_labels(render, Side::Top, labels);
+    }
 
-    let ticks = 10;
-    let iter = self.y.iter_ticks(data_bounds.y, ticks);
-    for (y, vy) in iter
-      .map(|t| {
-        let y = (transform * Point::new(0.0, t.position())).y;
-        (t, y)
+    for axes in &self.axes {
+      axes.draw(render, &self.viewport_transform(axes.binding(), bounds, viewport));
+    }
+
+    self.draw_legend(render, outer, viewport, bounds);
+  }
+
+  /// How the axis line on a side is drawn, or `None` if it isn't. `axis` is
+  /// the axis on that side, if there is one.
+  fn spine_stroke<'a>(&'a self, axis: Option<&'a Axis>) -> Option<&'a StrokeStyle> {
+    match (self.spines, axis) {
+      (Spines::None, _) | (Spines::LeftBottom, None) => None,
+      (_, Some(axis)) => axis.spine.stroke.as_ref().or(self.border.as_ref()),
+      (Spines::All, None) => self.border.as_ref(),
+    }
+  }
+
+  /// Draws the axis line along one side of the viewport, leaving a gap marked
+  /// with slashes at each break in the axis.
+  fn draw_spine(
+    &self,
+    render: &mut Render,
+    stroke: &StrokeStyle,
+    side: Side,
+    transform: &ViewportTransform,
+    viewport: Bounds,
+  ) {
+    const LINE_COLOR: Brush = Brush::Solid(Color::from_rgb8(128, 128, 128));
+    let brush = stroke.brush.as_ref().unwrap_or(&LINE_COLOR);
+
+    let (extent, breaks) = if side.is_vertical() {
+      (viewport.y.ascending(), &transform.y_breaks)
+    } else {
+      (viewport.x.ascending(), &transform.x_breaks)
+    };
+    let project = |v: f64| {
+      if side.is_vertical() {
+        (transform.affine * Point::new(0.0, v)).y
+      } else {
+        (transform.affine * Point::new(v, 0.0)).x
+      }
+    };
+    let mut gaps = breaks
+      .iter()
+      .map(|b| {
+        let start = bounds::collapse(breaks, b.from);
+        Range::new(project(start), project(start + b.gap)).ascending()
       })
-      .filter(|(_, vy)| viewport.y.contains(vy))
-    {
-      render.stroke(
-        &Line::new(Point::new(viewport.x.min, vy), Point::new(viewport.x.min - 10.0, vy)),
-        Affine::IDENTITY,
-        &LINE_COLOR,
-        &tick_stroke.clone().with_start_cap(Cap::Butt),
-      );
-      if let Some(stroke) = &self.grid {
+      .collect::<Vec<_>>();
+    gaps.sort_by(|a, b| a.min.total_cmp(&b.min));
+
+    let mut start = extent.min;
+    for gap in gaps.iter().chain([&Range::new(extent.max, extent.max)]) {
+      render.stroke(&side.spine(viewport, start, gap.min), Affine::IDENTITY, brush, &stroke.stroke);
+      start = gap.max;
+    }
+    for edge in gaps.iter().flat_map(|g| [g.min, g.max]) {
+      render.stroke(&side.break_mark(viewport, edge), Affine::IDENTITY, brush, &stroke.stroke);
+    }
+  }
+
+  /// Draws the ticks and gridlines of `axis`, along the given side of the
+  /// viewport. Only the primary axes draw the plot's grid. Returns the tick
+  /// labels and where they go, for the caller to draw.
+  fn draw_axis(
+    &self,
+    render: &mut Render,
+    axis: &Axis,
+    side: Side,
+    range: &DataRange,
+    transform: &ViewportTransform,
+    viewport: Bounds,
+  ) -> Vec<(String, Point)> {
+    const LINE_COLOR: Brush = Brush::Solid(Color::from_rgb8(128, 128, 128));
+
+    let project = |value: f64| side.project(transform, viewport, value);
+    let grid = match side {
+      Side::Left | Side::Bottom => self.grid.as_ref(),
+      Side::Right | Side::Top => None,
+    };
+
+    let ticks = axis.major_ticks(range.clone());
+    let spine = &axis.spine;
+    let at = axis.spine_bounds(side, transform, viewport);
+
+    if let Some(minor) = &axis.minor {
+      let stroke = minor.stroke.stroke.clone().with_start_cap(Cap::Butt);
+      for v in axis.minor_ticks_between(&ticks).into_iter().filter_map(project) {
+        if let Some(grid) = &minor.grid {
+          render.stroke(
+            &side.grid_line(viewport, v),
+            Affine::IDENTITY,
+            grid.brush.as_ref().unwrap_or(&LINE_COLOR),
+            &grid.stroke,
+          );
+        }
         render.stroke(
-          &Line::new(Point::new(viewport.x.min, vy), Point::new(viewport.x.max, vy)),
+          &side.tick(at, v, spine.tick_direction, minor.length),
           Affine::IDENTITY,
-          stroke.brush.as_ref().unwrap_or(&LINE_COLOR),
-          &stroke.stroke,
+          minor.stroke.brush.as_ref().unwrap_or(&LINE_COLOR),
+          &stroke,
         );
       }
-      render.draw_text(DrawText {
-        text: &y.to_string(),
-        size: 12.0,
-        position: Point { x: viewport.x.min - 15.0, y: vy },
-        brush: TEXT_COLOR,
-        horizontal_align: Align::End,
-        vertical_align: Align::Center,
-        ..Default::default()
-      });
     }
 
-    let iter = self.x.iter_ticks(data_bounds.x, ticks);
-    for (x, vx) in iter
-      .map(|t| {
-        let x = (transform * Point::new(t.position(), 0.0)).x;
-        (t, x)
-      })
-      .filter(|(_, vx)| viewport.x.contains(vx))
-    {
+    // Ticks follow the width and colour of their axis line, but are too short
+    // to show a dash pattern, so they are always solid.
+    let tick_style = spine.stroke.as_ref().or(self.border.as_ref());
+    let tick_stroke =
+      Stroke::new(tick_style.map_or(1.0, |style| style.stroke.width)).with_start_cap(Cap::Butt);
+    let tick_brush = tick_style.and_then(|style| style.brush.as_ref()).unwrap_or(&LINE_COLOR);
+    let label_offset = spine.label_offset();
+
+    let mut labels = vec![];
+    for (tick, v) in ticks.into_iter().filter_map(|t| project(t.position()).map(|v| (t, v))) {
       render.stroke(
-        &Line::new(Point::new(vx, viewport.y.min), Point::new(vx, viewport.y.min + 10.0)),
+        &side.tick(at, v, spine.tick_direction, spine.tick_length),
         Affine::IDENTITY,
-        &LINE_COLOR,
-        &tick_stroke.clone().with_start_cap(Cap::Butt),
+        tick_brush,
+        &tick_stroke,
       );
-      if let Some(stroke) = &self.grid {
+      if let Some(stroke) = grid {
         render.stroke(
-          &Line::new(Point::new(vx, viewport.y.min), Point::new(vx, viewport.y.max)),
+          &side.grid_line(viewport, v),
           Affine::IDENTITY,
           stroke.brush.as_ref().unwrap_or(&LINE_COLOR),
           &stroke.stroke,
         );
       }
-      render.draw_text(DrawText {
-        text: &x.to_string(),
-        size: 12.0,
-        position: Point { x: vx, y: viewport.y.min + 15.0 },
-        brush: TEXT_COLOR,
-        horizontal_align: Align::Center,
-        vertical_align: Align::Start,
-        ..Default::default()
-      });
+      labels.push((axis.tick_label(&tick), side.tick_line(at, v, label_offset).p1));
     }
+    labels
+  }
 
-    for axes in &self.axes {
-      axes.draw(render, transform);
+  /// Places the viewport inside `outer`, leaving each side enough room for
+  /// its ticks, tick labels and titles, unless [`Plot::margins`] says
+  /// otherwise.
+  fn layout(
+    &self,
+    render: &mut Render,
+    outer: Bounds,
+    bounds: &PlotBounds,
+    cell: CellLayout,
+  ) -> PlotLayout {
+    // The space kept between the outermost text and the edge of the plot.
+    const PADDING: f64 = 10.0;
+    // Tick labels at the ends of the right and top axes overhang the
+    // viewport by about half their size.
+    const OVERHANG: f64 = 30.0;
+
+    let (hidden, min) = (cell.hidden, cell.min_margins);
+    let margin =
+      |set: Option<f64>, fit: f64, min: Option<f64>| set.unwrap_or(fit).max(min.unwrap_or(0.0));
+    let x2_binding = AxisBinding { x2: true, y2: false };
+    let y2_binding = AxisBinding { x2: false, y2: true };
+    let x2_title = self.x2.as_ref().and_then(|x2| x2.title.as_ref());
+    let y2_title = self.y2.as_ref().and_then(|y2| y2.title.as_ref());
+
+    // The left and right sides are measured first, as how the bottom labels
+    // fit depends on how wide the viewport is.
+    let mut viewport = outer.shrink(PADDING);
+    let (left, right) = cell.sides.unwrap_or_else(|| {
+      let transform = self.viewport_transform(AxisBinding::default(), bounds, viewport);
+      let left = self.y.reach(render, Side::Left, &bounds.y, &transform, viewport, !hidden.y);
+      let right = match (&self.y2, &bounds.y2) {
+        (Some(y2), Some(range)) => {
+          let transform = self.viewport_transform(y2_binding, bounds, viewport);
+          y2.reach(render, Side::Right, range, &transform, viewport, true)
+        }
+        _ => 0.0,
+      };
+      (left, right)
+    });
+    let left_titles = title_height(render, self.y.title.as_ref(), 24.0, FontWeight::NORMAL);
+    let right_titles = title_height(render, y2_title, 24.0, FontWeight::NORMAL);
+    let (legend_right, legend_below) = self.legend_room(render);
+    let left_margin = margin(self.margins.left, PADDING + left_titles + left, min.left);
+    // An outside legend is drawn along the edge of the plot, so it is given
+    // room beyond any margin that was set.
+    let right_margin = margin(
+      self.margins.right.map(|right| right + legend_right),
+      (PADDING + right_titles + right + legend_right).max(OVERHANG),
+      min.right,
+    );
+    viewport.x = Range::new(outer.x.min + left_margin, outer.x.max - right_margin);
+
+    let transform = self.viewport_transform(AxisBinding::default(), bounds, viewport);
+    let bottom = self.x.reach(render, Side::Bottom, &bounds.x, &transform, viewport, !hidden.x);
+    let top = match (&self.x2, &bounds.x2) {
+      (Some(x2), Some(range)) => {
+        let transform = self.viewport_transform(x2_binding, bounds, viewport);
+        x2.reach(render, Side::Top, range, &transform, viewport, true)
+      }
+      _ => 0.0,
+    };
+    let bottom_titles = title_height(render, self.x.title.as_ref(), 24.0, FontWeight::NORMAL);
+    let top_titles = title_height(render, self.title.as_ref(), 32.0, FontWeight::BOLD)
+      + title_height(render, x2_title, 20.0, FontWeight::NORMAL);
+    let bottom_margin = margin(
+      self.margins.bottom.map(|bottom| bottom + legend_below),
+      PADDING + bottom_titles + bottom + legend_below,
+      min.bottom,
+    );
+    let top_margin = margin(self.margins.top, (PADDING + top_titles + top).max(OVERHANG), min.top);
+    viewport.y = Range::new(outer.y.min - bottom_margin, outer.y.max + top_margin);
+
+    let margins = Margins {
+      left:   Some(left_margin),
+      right:  Some(right_margin),
+      top:    Some(top_margin),
+      bottom: Some(bottom_margin),
+    };
+    if self.equal_aspect {
+      viewport = self.letterbox(viewport, bounds);
     }
+    PlotLayout { viewport, hidden, left, right, top, bottom, margins }
+  }
 
-    self.draw_legend(render, viewport);
+  /// Shrinks `viewport` around its center, so that the primary axes have the
+  /// same number of pixels per (scaled) unit.
+  fn letterbox(&self, viewport: Bounds, bounds: &PlotBounds) -> Bounds {
+    let x = self.x.scaled_range(&bounds.x).size().abs();
+    let y = self.y.scaled_range(&bounds.y).size().abs();
+    if !(x > 0.0 && y > 0.0) {
+      return viewport;
+    }
+
+    let scale = (viewport.width().abs() / x).min(viewport.height().abs() / y);
+    let fit = |range: Range, size: f64| {
+      let half = size / 2.0 * range.size().signum();
+      Range::new(range.center() - half, range.center() + half)
+    };
+    Bounds::new(fit(viewport.x, x * scale), fit(viewport.y, y * scale))
+  }
+
+  /// Draws a crosshair at `cursor` (in logical coordinates), along with the
+  /// data-space coordinates under it in the top right corner of `outer`.
+  fn draw_cursor_in(
+    &self,
+    render: &mut Render,
+    outer: Bounds,
+    bounds: &PlotBounds,
+    layout: &PlotLayout,
+    cursor: Point,
+  ) {
+    const TEXT_COLOR: Brush = Brush::Solid(Color::from_rgb8(32, 32, 32));
+    const LINE_COLOR: Brush = Brush::Solid(Color::from_rgba8(64, 64, 64, 160));
+
+    let viewport = layout.viewport;
+    if !viewport.x.contains(&cursor.x) || !viewport.y.contains(&cursor.y) {
+      return;
+    }
+
+    let transform = self.viewport_transform(AxisBinding::default(), bounds, viewport);
+    let value = transform.inverse(cursor);
+
+    let stroke = Stroke::new(1.0);
+    render.stroke(
+      &Line::new(Point::new(viewport.x.min, cursor.y), Point::new(viewport.x.max, cursor.y)),
+      Affine::IDENTITY,
+      &LINE_COLOR,
+      &stroke,
+    );
+    render.stroke(
+      &Line::new(Point::new(cursor.x, viewport.y.min), Point::new(cursor.x, viewport.y.max)),
+      Affine::IDENTITY,
+      &LINE_COLOR,
+      &stroke,
+    );
+
+    let mut text = format!(
+      "x: {}  y: {}",
+      self.x.format_value(&bounds.x, value.x),
+      self.y.format_value(&bounds.y, value.y),
+    );
+    if let (Some(x2), Some(range)) = (&self.x2, &bounds.x2) {
+      let binding = AxisBinding { x2: true, y2: false };
+      let value = self.viewport_transform(binding, bounds, viewport).inverse(cursor);
+      text += &format!("  x2: {}", x2.format_value(range, value.x));
+    }
+    if let (Some(y2), Some(range)) = (&self.y2, &bounds.y2) {
+      let binding = AxisBinding { x2: false, y2: true };
+      let value = self.viewport_transform(binding, bounds, viewport).inverse(cursor);
+      text += &format!("  y2: {}", y2.format_value(range, value.y));
+    }
+    render.draw_text(DrawText {
+      text: &text,
+      size: 16.0,
+      position: Point { x: outer.x.max - 10.0, y: outer.y.max + 10.0 },
+      brush: TEXT_COLOR,
+      horizontal_align: Align::End,
+      vertical_align: Align::Start,
+      ..Default::default()
+    });
   }
 }
 
 enum TicksIter<'a> {
-  Auto { iter: bounds::NiceTicksIter, scale: Scale, unit: RangeUnit },
+  Auto {
+    iter:  bounds::NiceTicksIter,
+    scale: Scale,
+    unit:  RangeUnit,
+  },
+  Values {
+    iter:      std::vec::IntoIter<f64>,
+    precision: u32,
+    unit:      RangeUnit,
+  },
+  Powers {
+    iter:  std::iter::StepBy<std::ops::RangeInclusive<i32>>,
+    base:  f64,
+    style: PowerStyle,
+  },
+  Calendar {
+    iter: std::vec::IntoIter<f64>,
+    unit: RangeUnit,
+    step: calendar::TimeStep,
+    year: Option<i32>,
+  },
   Fixed(FixedTicksIter),
-  Labeled(ColumnIter<'a>),
+  Manual {
+    iter: std::vec::IntoIter<(f64, Option<String>)>,
+    unit: RangeUnit,
+  },
+  Labeled(std::iter::Enumerate<std::vec::IntoIter<AnyValue<'a>>>),
 }
 
 struct FixedTicksIter {
@@ -399,23 +1240,29 @@ struct FixedTicksIter {
   step:    f64,
 }
 
-struct ColumnIter<'a> {
-  column:  &'a Column,
-  current: usize,
-}
-
 #[derive(Clone)]
 enum Tick<'a> {
   Auto { value: f64, precision: u32, unit: RangeUnit },
+  Power { base: f64, exponent: i32, style: PowerStyle },
+  Calendar { value: f64, unit: RangeUnit, step: calendar::TimeStep, new_year: bool },
   Fixed { value: f64 },
+  Manual { value: f64, label: String },
   Label { label: AnyValue<'a>, index: usize },
 }
 
 impl Tick<'_> {
+  /// A tick at `value`, labeled with as few decimals as show it exactly.
+  fn value(value: f64, unit: RangeUnit) -> Self {
+    Tick::Auto { value, precision: decimals(value) + 3, unit }
+  }
+
   fn position(&self) -> f64 {
     match self {
       Tick::Auto { value, .. } => *value,
+      Tick::Power { base, exponent, .. } => base.powi(*exponent),
+      Tick::Calendar { value, .. } => *value,
       Tick::Fixed { value } => *value,
+      Tick::Manual { value, .. } => *value,
       Tick::Label { index, .. } => *index as f64,
     }
   }
@@ -423,50 +1270,412 @@ impl Tick<'_> {
 
 impl Axis {
   fn iter_ticks<'a>(&self, range: DataRange<'a>, nice_ticks: u32) -> TicksIter<'a> {
+    let pretty = self.pretty_range(&range);
+    self.iter_ticks_over(range, pretty, nice_ticks)
+  }
+
+  /// Ticks for `range`, where `pretty` is the part of the axis they may be
+  /// drawn over.
+  fn iter_ticks_over<'a>(
+    &self,
+    range: DataRange<'a>,
+    pretty: Range,
+    nice_ticks: u32,
+  ) -> TicksIter<'a> {
     match &self.ticks {
       Ticks::Auto => match range {
-        DataRange::Categorical(labels) => {
-          TicksIter::Labeled(ColumnIter { column: labels, current: 0 })
+        DataRange::Categorical(labels) => TicksIter::Labeled(labels.into_iter().enumerate()),
+        DataRange::Continuous {
+          unit: unit @ (RangeUnit::Date | RangeUnit::Datetime(..)), ..
+        } if matches!(self.scale, Scale::Linear) => {
+          let (step, ticks) = calendar::ticks(pretty, unit, nice_ticks);
+          TicksIter::Calendar { iter: ticks.into_iter(), unit, step, year: None }
         }
-        DataRange::Continuous { range, unit, .. } => {
-          let range = match self.scale {
-            Scale::Linear => range,
-            Scale::Logarithmic => Range::new(range.min.log10(), range.max.log10()),
-          };
-          TicksIter::Auto { iter: range.nice_ticks(nice_ticks), scale: self.scale, unit }
+        DataRange::Continuous { unit: unit @ RangeUnit::Duration(time_unit), .. }
+          if matches!(self.scale, Scale::Linear) =>
+        {
+          let ticks = calendar::duration_ticks(pretty, time_unit, nice_ticks);
+          TicksIter::values(ticks, unit)
         }
+        DataRange::Continuous { range: r, unit, .. } => match &self.scale {
+          Scale::Linear => {
+            TicksIter::Auto { iter: r.nice_ticks(nice_ticks), scale: Scale::Linear, unit }
+          }
+          &Scale::Logarithmic { base } => {
+            // Decades come from the data and any explicit limits, so margins
+            // don't add a power of the base at either end.
+            let r = r.ascending();
+            let limits = Range::new(
+              if self.min.is_some() { pretty.min } else { r.min },
+              if self.max.is_some() { pretty.max } else { r.max },
+            );
+            let scaled = limits.map(|v| self.scale.scale_value(v));
+            let lo = scaled.min.floor() as i32;
+            let hi = scaled.max.ceil() as i32;
+            let visible = scaled.max.floor() - scaled.min.ceil() + 1.0;
+
+            if visible >= 2.0 {
+              let stride = ((hi - lo) as u32).div_ceil(nice_ticks.max(1)).max(1);
+              return TicksIter::Powers {
+                iter: (lo..=hi).step_by(stride as usize),
+                base,
+                style: self.power_style,
+              };
+            }
+
+            // Less than a decade is visible, so try ticks at 1, 2 and 5 times each
+            // power, and otherwise fall back to linear ticks.
+            if base == 10.0 {
+              let ticks = (lo..=hi)
+                .flat_map(|e| [1.0, 2.0, 5.0].map(|k| k * base.powi(e)))
+                .filter(|v| pretty.contains(v))
+                .collect::<Vec<_>>();
+              if ticks.len() >= 3 {
+                return TicksIter::values(ticks, unit);
+              }
+            }
+            TicksIter::Auto { iter: pretty.nice_ticks(nice_ticks), scale: Scale::Linear, unit }
+          }
+          &Scale::SymLog { linthresh } => {
+            if pretty.min.abs().max(pretty.max.abs()) <= linthresh {
+              // Never leaves the linear region, so there are no decades to tick.
+              TicksIter::Auto { iter: r.nice_ticks(nice_ticks), scale: Scale::Linear, unit }
+            } else {
+              TicksIter::values(pretty.symlog_ticks(linthresh, nice_ticks), unit)
+            }
+          }
+          Scale::Custom(scale) => match scale.ticks(pretty, nice_ticks) {
+            Some(ticks) => TicksIter::values(ticks, unit),
+            None => TicksIter::Auto { iter: r.nice_ticks(nice_ticks), scale: Scale::Linear, unit },
+          },
+        },
       },
-      Ticks::Fixed(count) => {
-        TicksIter::Fixed(FixedTicksIter::new(self.pretty_range(range), *count))
+      Ticks::Fixed(count) => TicksIter::Fixed(FixedTicksIter::new(pretty, *count)),
+      Ticks::Manual(ticks) => {
+        TicksIter::Manual { iter: ticks.clone().into_iter(), unit: range.unit() }
+      }
+    }
+  }
+
+  /// The major ticks along this axis, thinned out to [`Axis::max_ticks`].
+  fn major_ticks<'a>(&self, range: DataRange<'a>) -> Vec<Tick<'a>> {
+    let count = self.max_ticks.unwrap_or(10);
+    let breaks = self.scaled_breaks(&range);
+    let ticks = match range {
+      DataRange::Continuous { unit, .. }
+        if matches!(self.ticks, Ticks::Auto) && !breaks.is_empty() =>
+      {
+        // Pick ticks separately on each side of every break, sharing out the
+        // tick count by how much of the axis each side covers.
+        let scaled = self.pretty_range(&range).map(|v| self.scale.scale_value(v));
+        let total = bounds::collapse(&breaks, scaled.max) - bounds::collapse(&breaks, scaled.min);
+
+        let mut edges = vec![scaled.min];
+        edges.extend(breaks.iter().flat_map(|b| [b.from, b.to]));
+        edges.push(scaled.max);
+        edges
+          .chunks(2)
+          .flat_map(|edges| {
+            let share = (edges[1] - edges[0]) / total;
+            let segment =
+              Range::new(self.scale.unscale_value(edges[0]), self.scale.unscale_value(edges[1]));
+            let range =
+              DataRange::Continuous { range: segment, unit, margin_min: false, margin_max: false };
+            let count = ((f64::from(count) * share).round() as u32).max(2);
+            // The axis limits are already applied at the outer edges, so each
+            // segment picks its ticks from its own range. Ticks are kept in
+            // scaled space, so that rounding doesn't drop one at an edge.
+            let slack = (edges[1] - edges[0]).abs() * 1e-9;
+            let (from, to) = (edges[0] - slack, edges[1] + slack);
+            self
+              .iter_ticks_over(range, segment, count)
+              .filter(move |t| (from..=to).contains(&self.scale.scale_value(t.position())))
+          })
+          .collect()
+      }
+      _ => {
+        self.iter_ticks(range, count).filter(|t| !self.in_break(t.position())).collect::<Vec<_>>()
+      }
+    };
+    match self.max_ticks {
+      Some(max) if matches!(ticks.first(), Some(Tick::Label { .. })) => {
+        let stride = ticks.len().div_ceil(max.max(1) as usize).max(1);
+        ticks.into_iter().step_by(stride).collect()
+      }
+      _ => ticks,
+    }
+  }
+
+  /// Positions of the minor ticks between each pair of `majors`.
+  fn minor_ticks_between(&self, majors: &[Tick]) -> Vec<f64> {
+    let Some(minor) = &self.minor else { return vec![] };
+
+    let mut ticks = vec![];
+    for pair in majors.windows(2) {
+      let span = Range::new(pair[0].position(), pair[1].position()).ascending();
+      if self.breaks.iter().any(|b| b.min < span.max && b.max > span.min) {
+        continue;
+      }
+      match (&pair[0], &pair[1]) {
+        (Tick::Label { .. }, _) | (_, Tick::Label { .. }) => return vec![],
+        (&Tick::Power { base, exponent: a, .. }, &Tick::Power { exponent: b, .. })
+          if minor.subdivisions.is_none() && (b - a > 1 || base.fract() == 0.0) =>
+        {
+          if b - a > 1 {
+            // Decades were skipped, so the skipped decades become minor ticks.
+            ticks.extend((a + 1..b).map(|e| base.powi(e)));
+          } else {
+            let decade = base.powi(a);
+            ticks.extend((2..base as i32).map(|k| decade * f64::from(k)));
+          }
+        }
+        (a, b) => {
+          let a = self.scale.scale_value(a.position());
+          let b = self.scale.scale_value(b.position());
+          let n = minor.subdivisions.unwrap_or_else(|| {
+            // Steps of 2 split evenly into 4, everything else into 5.
+            let step = (b - a).abs();
+            let mantissa = step / 10_f64.powf(step.log10().floor());
+            if (mantissa - 2.0).abs() < 1e-6 { 4 } else { 5 }
+          });
+          ticks.extend((1..n).map(|i| self.scale.unscale_value(a + (b - a) * i as f64 / n as f64)));
+        }
       }
     }
+    ticks.retain(|&v| !self.in_break(v));
+    ticks
   }
 
-  fn pretty_range(&self, r: DataRange) -> Range {
+  /// Formats a single data-space value the same way this axis formats its
+  /// ticks, with one extra digit of precision over the tick labels.
+  fn format_value(&self, range: &DataRange, value: f64) -> String {
+    match range {
+      DataRange::Categorical(labels) => {
+        let index = value.round();
+        if index < 0.0 || index >= labels.len() as f64 {
+          return String::new();
+        }
+        let index = index as usize;
+        match labels.get(index) {
+          Some(label) => bounds::category_name(label),
+          None => String::new(),
+        }
+      }
+      DataRange::Continuous { .. } if self.format.is_some() => {
+        self.tick_label(&Tick::Fixed { value })
+      }
+      DataRange::Continuous { unit, .. } => {
+        let step = match &self.scale {
+          Scale::Linear => self.pretty_range(range).size() / 100.0,
+          Scale::Logarithmic { .. } => value / 100.0,
+          Scale::SymLog { linthresh } => value.abs().max(*linthresh) / 100.0,
+          Scale::Custom(scale) => {
+            let scaled = self.pretty_range(range).map(|v| scale.forward(v));
+            scale.inverse(scale.forward(value) + scaled.size() / 100.0) - value
+          }
+        };
+        let precision = (-step.abs().log10().floor() as i32 + 4).max(0) as u32;
+        Tick::Auto { value, precision, unit: *unit }.to_string()
+      }
+    }
+  }
+
+  /// The label for `tick`, using the formatter from [`Axis::format_ticks`] if
+  /// one was set. Categorical and manual labels are never reformatted.
+  fn tick_label(&self, tick: &Tick) -> String {
+    match (&self.format, tick) {
+      (_, Tick::Label { .. } | Tick::Manual { .. }) | (None, _) => tick.to_string(),
+      (Some(format), tick) => format(tick.position()),
+    }
+  }
+
+  fn pretty_range(&self, r: &DataRange) -> Range {
     match r {
       DataRange::Continuous { range, margin_min, margin_max, .. } => {
+        let range = range.ascending();
         let mut r = range;
-        if margin_min {
+        let scaled = range.map(|v| self.scale.scale_value(v));
+        // Margins are relative to the visible part of the axis, so broken
+        // intervals don't count towards them.
+        let broken: f64 = self
+          .breaks
+          .iter()
+          .map(|b| {
+            let b = b.map(|v| self.scale.scale_value(v.clamp(range.min, range.max)));
+            b.size().abs()
+          })
+          .sum();
+        let margin = ((scaled.size() - broken) * self.margin).abs();
+        if *margin_min {
           match self.scale {
-            Scale::Linear => r.min -= (r.size() * self.margin).abs(),
-            Scale::Logarithmic => {
-              r.min -= 10_f64.powf(((r.max.log10() - r.min.log10()) * self.margin).abs())
+            Scale::Linear => r.min -= margin,
+            Scale::Logarithmic { .. } | Scale::SymLog { .. } | Scale::Custom(_) => {
+              r.min = self.scale.unscale_value(scaled.min - margin)
             }
           }
         }
-        if margin_max {
+        if *margin_max {
           match self.scale {
-            Scale::Linear => r.max += (r.size() * self.margin).abs(),
-            Scale::Logarithmic => {
-              r.max += 10_f64.powf(((r.max.log10() - r.min.log10()) * self.margin).abs())
+            Scale::Linear => r.max += margin,
+            Scale::Logarithmic { .. } | Scale::SymLog { .. } | Scale::Custom(_) => {
+              r.max = self.scale.unscale_value(scaled.max + margin)
             }
           }
         }
-        Range::new(self.min.unwrap_or(r.min), self.max.unwrap_or(r.max))
+        Range::new(self.min.unwrap_or(r.min), self.max.unwrap_or(r.max)).ascending()
       }
       DataRange::Categorical(labels) => Range::new(-0.5, labels.len() as f64 - 0.5),
     }
   }
+
+  /// How far this axis's ticks and tick labels reach out from `side` of the
+  /// viewport. An axis line moved into the plot takes no room outside it.
+  fn reach(
+    &self,
+    render: &mut Render,
+    side: Side,
+    range: &DataRange,
+    transform: &ViewportTransform,
+    viewport: Bounds,
+    labels: bool,
+  ) -> f64 {
+    if self.spine.position != SpinePosition::Edge {
+      return 0.0;
+    }
+    let labels = if labels {
+      self
+        .major_ticks(range.clone())
+        .into_iter()
+        .filter_map(|tick| {
+          let v = side.project(transform, viewport, tick.position())?;
+          let at = if side.is_vertical() { Point::new(0.0, v) } else { Point::new(v, 0.0) };
+          Some((self.tick_label(&tick), at))
+        })
+        .collect::<Vec<_>>()
+    } else {
+      vec![]
+    };
+
+    if labels.is_empty() {
+      match self.spine.tick_direction {
+        TickDirection::Out | TickDirection::Both => self.spine.tick_length,
+        TickDirection::In => 0.0,
+      }
+    } else {
+      self.spine.label_offset() + self.tick_label_extent(render, side, &labels)
+    }
+  }
+
+  /// The viewport, with the edge on `side` moved to where this axis's line is
+  /// placed. A position off the plot puts the line on the nearest edge.
+  fn spine_bounds(&self, side: Side, transform: &ViewportTransform, viewport: Bounds) -> Bounds {
+    let value = match self.spine.position {
+      SpinePosition::Edge => return viewport,
+      SpinePosition::Zero => 0.0,
+      SpinePosition::Value(value) => value,
+    };
+
+    let mut at = viewport;
+    if side.is_vertical() {
+      let x = viewport.x.ascending();
+      let x = (transform * Point::new(value, 0.0)).x.clamp(x.min, x.max);
+      if side == Side::Left { at.x.min = x } else { at.x.max = x }
+    } else {
+      let y = viewport.y.ascending();
+      let y = (transform * Point::new(0.0, value)).y.clamp(y.min, y.max);
+      if side == Side::Bottom { at.y.min = y } else { at.y.max = y }
+    }
+    at
+  }
+
+  /// Reorders a categorical range to follow [`Axis::categories`].
+  fn ordered<'a>(&self, range: DataRange<'a>) -> DataRange<'a> {
+    match (range, &self.categories) {
+      (DataRange::Categorical(labels), Some(order)) => {
+        let listed = order.iter().map(String::as_str).collect::<HashSet<_>>();
+        let rest =
+          labels.into_iter().filter(|v| !listed.contains(bounds::category_name(v).as_str()));
+        DataRange::Categorical(
+          order.iter().map(|name| AnyValue::StringOwned(name.into())).chain(rest).collect(),
+        )
+      }
+      (range, _) => range,
+    }
+  }
+
+  /// The visible range of this axis, in scaled space and oriented the way it
+  /// is drawn.
+  fn scaled_range(&self, range: &DataRange) -> Range {
+    let breaks = self.scaled_breaks(range);
+    self
+      .oriented(self.pretty_range(range))
+      .map(|v| bounds::collapse(&breaks, self.scale.scale_value(v)))
+  }
+
+  /// The breaks that fall inside the visible range, in scaled space. Each one
+  /// is collapsed down to a gap of 3% of the axis.
+  fn scaled_breaks(&self, range: &DataRange) -> Vec<bounds::Break> {
+    if matches!(range, DataRange::Categorical(_)) {
+      return vec![];
+    }
+    let pretty = self.pretty_range(range);
+    let mut breaks = self
+      .breaks
+      .iter()
+      .filter(|b| b.min > pretty.min && b.max < pretty.max)
+      .map(|b| bounds::Break {
+        from: self.scale.scale_value(b.min),
+        to:   self.scale.scale_value(b.max),
+        gap:  0.0,
+      })
+      .collect::<Vec<_>>();
+
+    // Each gap takes up 3% of what remains visible of the axis.
+    let kept = pretty.map(|v| self.scale.scale_value(v)).size().abs()
+      - breaks.iter().map(|b| b.to - b.from).sum::<f64>();
+    let gap = kept * 0.03;
+    breaks.retain(|b| b.to - b.from > gap);
+    for b in &mut breaks {
+      b.gap = gap;
+    }
+    breaks
+  }
+
+  /// Whether `value` is hidden inside one of this axis's breaks.
+  fn in_break(&self, value: f64) -> bool {
+    self.breaks.iter().any(|b| value > b.min && value < b.max)
+  }
+
+  /// Flips the given ascending range if this axis is inverted.
+  fn oriented(&self, range: Range) -> Range {
+    if self.inverted { Range::new(range.max, range.min) } else { range }
+  }
+}
+
+impl TicksIter<'_> {
+  /// Ticks at the given values, all labeled with enough decimals to tell the
+  /// closest pair apart and to show each value exactly.
+  fn values(values: Vec<f64>, unit: RangeUnit) -> Self {
+    let spacing = values.windows(2).map(|w| (w[1] - w[0]).abs()).fold(f64::INFINITY, f64::min);
+    let spacing = if spacing > 0.0 && spacing.is_finite() {
+      (-spacing.log10().floor() as i32).max(0) as u32
+    } else {
+      0
+    };
+    let exact = values.iter().map(|&v| decimals(v)).max().unwrap_or(0);
+    TicksIter::Values { iter: values.into_iter(), precision: spacing.max(exact) + 3, unit }
+  }
+}
+
+/// The fewest decimals that `value` can be printed with, without rounding it.
+fn decimals(value: f64) -> u32 {
+  const MAX: u32 = 9;
+  (0..MAX)
+    .find(|&d| {
+      let scaled = value * 10_f64.powi(d as i32);
+      (scaled - scaled.round()).abs() <= 1e-9 * scaled.abs().max(1.0)
+    })
+    .unwrap_or(MAX)
 }
 
 impl<'a> Iterator for TicksIter<'a> {
@@ -475,14 +1684,26 @@ impl<'a> Iterator for TicksIter<'a> {
   fn next(&mut self) -> Option<Self::Item> {
     match self {
       TicksIter::Auto { iter, scale, unit } => iter.next().map(|v| Tick::Auto {
-        value:     match scale {
-          Scale::Linear => v,
-          Scale::Logarithmic => 10f64.powf(v),
-        },
+        value:     scale.unscale_value(v),
         precision: iter.precision() as u32,
         unit:      *unit,
       }),
+      TicksIter::Values { iter, precision, unit } => {
+        iter.next().map(|value| Tick::Auto { value, precision: *precision, unit: *unit })
+      }
+      TicksIter::Powers { iter, base, style } => {
+        iter.next().map(|exponent| Tick::Power { base: *base, exponent, style: *style })
+      }
+      TicksIter::Calendar { iter, unit, step, year } => iter.next().map(|value| {
+        let prev = *year;
+        *year = calendar::year(value, *unit);
+        Tick::Calendar { value, unit: *unit, step: *step, new_year: prev != *year }
+      }),
       TicksIter::Fixed(iter) => iter.next().map(|v| Tick::Fixed { value: v }),
+      TicksIter::Manual { iter, unit } => iter.next().map(|(value, label)| match label {
+        Some(label) => Tick::Manual { value, label },
+        None => Tick::value(value, *unit),
+      }),
       TicksIter::Labeled(iter) => iter.next().map(|(i, v)| Tick::Label { label: v, index: i }),
     }
   }
@@ -513,47 +1734,67 @@ impl Iterator for FixedTicksIter {
   }
 }
 
-impl<'a> Iterator for ColumnIter<'a> {
-  type Item = (usize, AnyValue<'a>);
-
-  fn next(&mut self) -> Option<Self::Item> {
-    if self.current >= self.column.len() {
-      None
-    } else {
-      let v = self.column.get(self.current).unwrap();
-      let curr = self.current;
-      self.current += 1;
-      Some((curr, v))
-    }
-  }
-}
-
 impl fmt::Display for Tick<'_> {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     match &self {
       Tick::Auto { value, precision, unit: RangeUnit::Absolute } => {
         write!(f, "{value:.*}", precision.saturating_sub(3) as usize)
       }
-      Tick::Auto { value, precision: _, unit: RangeUnit::Duration } => {
-        write!(f, "{:?}", std::time::Duration::from_nanos(*value as u64))
+      Tick::Auto { value, precision: _, unit: RangeUnit::Duration(time_unit) } => {
+        write!(f, "{}", calendar::format_duration(*value, *time_unit))
       }
       Tick::Auto { value, precision: _, unit: RangeUnit::Date } => {
         write!(f, "{}", AnyValue::Date(*value as i32))
       }
+      Tick::Auto { value, precision: _, unit: unit @ RangeUnit::Datetime(..) } => {
+        write!(f, "{}", calendar::format(*value, *unit, None, false))
+      }
+      Tick::Calendar { value, unit, step, new_year } => {
+        write!(f, "{}", calendar::format(*value, *unit, Some(*step), *new_year))
+      }
+      Tick::Power { base: 10.0, exponent, style: PowerStyle::Prefix }
+        if (-12..15).contains(exponent) =>
+      {
+        const PREFIXES: [&str; 9] = ["p", "n", "µ", "m", "", "k", "M", "G", "T"];
+        let prefix = PREFIXES[(exponent.div_euclid(3) + 4) as usize];
+        write!(f, "{}{prefix}", 10_i32.pow(exponent.rem_euclid(3) as u32))
+      }
+      Tick::Power { base, exponent, .. } if *base == std::f64::consts::E => {
+        write!(f, "e^{exponent}")
+      }
+      Tick::Power { base, exponent, .. } => write!(f, "{base}^{exponent}"),
       Tick::Fixed { value } => write!(f, "{value:.2}"),
-      Tick::Label { label, .. } => match label {
-        AnyValue::String(s) => write!(f, "{s}"),
-        AnyValue::StringOwned(s) => write!(f, "{s}"),
-        _ => write!(f, "{label}"),
-      },
+      Tick::Manual { label, .. } => write!(f, "{label}"),
+      Tick::Label { label, .. } => write!(f, "{}", bounds::category_name(label)),
     }
   }
 }
 
 #[cfg(test)]
 mod tests {
+  use std::collections::HashMap;
+
+  use polars::prelude::{Column, DataType, TimeUnit};
+
   use super::*;
 
+  /// Tick labels as drawn, each with the point it is anchored at.
+  type TickLabels = Vec<(String, Point)>;
+
+  /// The viewport `plot` is drawn in, with the tick labels drawn along its
+  /// left and bottom axes.
+  fn drawn_tick_labels(plot: &Plot) -> (Bounds, TickLabels, TickLabels) {
+    let mut render = Render::new();
+    let outer = render.bounds();
+    let bounds = plot.bounds();
+    let viewport = plot.layout(&mut render, outer, &bounds, CellLayout::default()).viewport;
+    let transform = plot.viewport_transform(AxisBinding::default(), &bounds, viewport);
+    let left = plot.draw_axis(&mut render, &plot.y, Side::Left, &bounds.y, &transform, viewport);
+    let bottom =
+      plot.draw_axis(&mut render, &plot.x, Side::Bottom, &bounds.x, &transform, viewport);
+    (viewport, left, bottom)
+  }
+
   #[test]
   fn fixed_iter_works() {
     let iter = FixedTicksIter::new(Range::new(0.0, 1.0), 5);
@@ -566,4 +1807,808 @@ mod tests {
     let results: Vec<f64> = iter.collect();
     assert_eq!(results, vec![0.0, 0.5, 1.0, 1.5, 2.0]);
   }
+
+  #[test]
+  fn viewport_transform_inverts() {
+    let transform = ViewportTransform {
+      affine:   Bounds::new(Range::new(0.0, 3.0), Range::new(0.0, 10.0))
+        .transform_to(Bounds::new(Range::new(80.0, 920.0), Range::new(920.0, 80.0))),
+      x:        Scale::Logarithmic { base: 10.0 },
+      y:        Scale::Linear,
+      x_breaks: vec![],
+      y_breaks: vec![],
+
+      x_categories: HashMap::new(),
+      y_categories: HashMap::new(),
+      x_unit:       RangeUnit::Absolute,
+      y_unit:       RangeUnit::Absolute,
+    };
+
+    let data = Point::new(100.0, 2.5);
+    let screen = &transform * data;
+    let back = transform.inverse(screen);
+    assert!((back.x - data.x).abs() < 1e-9);
+    assert!((back.y - data.y).abs() < 1e-9);
+  }
+
+  #[test]
+  fn inverted_axes_start_at_the_far_end() {
+    let values = Column::new("v".into(), [1.0, 100.0]);
+    let mut plot = Plot::new();
+    plot.line(&values, &values);
+    plot.x.invert().margin(0.0);
+    plot.y.log_scale().invert().margin(0.0);
+
+    let mut render = Render::new();
+    let outer = render.bounds();
+    let bounds = plot.bounds();
+    let viewport = plot.layout(&mut render, outer, &bounds, CellLayout::default()).viewport;
+    let transform = plot.viewport_transform(AxisBinding::default(), &bounds, viewport);
+
+    // The smallest values land on the right and at the top of the viewport.
+    let min = &transform * Point::new(1.0, 1.0);
+    let max = &transform * Point::new(100.0, 100.0);
+    assert!((min.x - viewport.x.max).abs() < 1e-6 && (min.y - viewport.y.max).abs() < 1e-6);
+    assert!((max.x - viewport.x.min).abs() < 1e-6 && (max.y - viewport.y.min).abs() < 1e-6);
+
+    // Halfway up a log axis is the geometric mean.
+    let mid = &transform * Point::new(10.0, 10.0);
+    assert!((mid.y - viewport.y.center()).abs() < 1e-6);
+  }
+
+  #[test]
+  fn symlog_ticks_work() {
+    let scale = Scale::SymLog { linthresh: 1.0 };
+    for v in [-1000.0, -0.5, 0.0, 0.5, 3.0, 1e6] {
+      assert!((scale.unscale_value(scale.scale_value(v)) - v).abs() < 1e-6 * v.abs().max(1.0));
+    }
+
+    let ticks = Range::new(-150.0, 2000.0).symlog_ticks(1.0, 10);
+    assert_eq!(ticks, vec![-100.0, -10.0, -1.0, 0.0, 1.0, 10.0, 100.0, 1000.0]);
+
+    // Evenly spaced within the threshold, and a decade per unit past it.
+    let scale = Scale::SymLog { linthresh: 2.0 };
+    let inside = [-2.0, -1.0, 0.0, 1.0, 2.0].map(|v| scale.scale_value(v));
+    assert_eq!(inside, [-1.0, -0.5, 0.0, 0.5, 1.0]);
+    assert_eq!(scale.scale_value(20.0), 2.0);
+    assert_eq!(scale.scale_value(-200.0), -3.0);
+    assert!((scale.scale_value(2.0 + 1e-9) - 1.0).abs() < 1e-6);
+  }
+
+  #[test]
+  #[should_panic(expected = "symlog threshold")]
+  fn symlog_rejects_zero_threshold() { Axis::default().symlog_scale(0.0); }
+
+  #[test]
+  #[should_panic(expected = "log base")]
+  fn log_rejects_base_one() { Axis::default().log_scale_base(1.0); }
+
+  #[test]
+  fn log_base_ticks_work() {
+    let mut axis = Axis::default();
+    axis.log_scale_base(2.0);
+
+    let range = DataRange::from(Range::new(1.0, 1024.0));
+    let ticks: Vec<String> = axis.iter_ticks(range, 10).map(|t| t.to_string()).collect();
+    assert_eq!(
+      ticks,
+      ["2^0", "2^1", "2^2", "2^3", "2^4", "2^5", "2^6", "2^7", "2^8", "2^9", "2^10"]
+    );
+  }
+
+  #[test]
+  fn custom_scale_works() {
+    struct Sqrt;
+
+    impl ScaleFn for Sqrt {
+      fn forward(&self, value: f64) -> f64 { value.sqrt() }
+      fn inverse(&self, value: f64) -> f64 { value * value }
+      fn ticks(&self, _: Range, _: u32) -> Option<Vec<f64>> { Some(vec![0.0, 1.0, 4.0, 9.0]) }
+    }
+
+    let mut axis = Axis::default();
+    axis.custom_scale(Sqrt).margin(0.0);
+
+    let range = DataRange::from(Range::new(0.0, 9.0));
+    let ticks: Vec<f64> = axis.iter_ticks(range, 10).map(|t| t.position()).collect();
+    assert_eq!(ticks, [0.0, 1.0, 4.0, 9.0]);
+    assert_eq!(axis.scale.scale_value(4.0), 2.0);
+    assert_eq!(axis.scale.unscale_value(3.0), 9.0);
+  }
+
+  #[test]
+  fn custom_scale_labels_keep_decimals() {
+    struct Quarters;
+
+    impl ScaleFn for Quarters {
+      fn forward(&self, value: f64) -> f64 { value }
+      fn inverse(&self, value: f64) -> f64 { value }
+      fn ticks(&self, _: Range, _: u32) -> Option<Vec<f64>> { Some(vec![0.25, 1.5, 2.5]) }
+    }
+
+    let mut axis = Axis::default();
+    axis.custom_scale(Quarters);
+
+    let range = DataRange::from(Range::new(0.0, 3.0));
+    let labels: Vec<String> = axis.iter_ticks(range, 10).map(|t| t.to_string()).collect();
+    assert_eq!(labels, ["0.25", "1.50", "2.50"]);
+  }
+
+  #[test]
+  fn minor_ticks_work() {
+    let mut axis = Axis::default();
+    axis.log_scale().minor_ticks_auto();
+
+    let range = DataRange::from(Range::new(1.0, 100.0));
+    let majors: Vec<Tick> = axis.iter_ticks(range, 10).collect();
+    let minors = axis.minor_ticks_between(&majors);
+    assert_eq!(minors.len(), 8 * (majors.len() - 1));
+    assert_eq!(
+      minors[..8],
+      [2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0].map(|v| v * majors[0].position())
+    );
+
+    let mut axis = Axis::default();
+    axis.minor_ticks(4);
+    let majors = [Tick::Fixed { value: 0.0 }, Tick::Fixed { value: 1.0 }];
+    assert_eq!(axis.minor_ticks_between(&majors), [0.25, 0.5, 0.75]);
+  }
+
+  #[test]
+  fn log_ticks_ignore_margins() {
+    let mut axis = Axis::default();
+    axis.log_scale().margin(0.5);
+
+    let range = DataRange::from(Range::new(10.0, 1000.0));
+    let ticks: Vec<String> = axis.iter_ticks(range.clone(), 10).map(|t| t.to_string()).collect();
+    assert_eq!(ticks, ["10^1", "10^2", "10^3"]);
+
+    // Explicit limits do reach past the data.
+    axis.min(1.0);
+    let ticks: Vec<String> = axis.iter_ticks(range, 10).map(|t| t.to_string()).collect();
+    assert_eq!(ticks, ["10^0", "10^1", "10^2", "10^3"]);
+  }
+
+  #[test]
+  fn decade_ticks_work() {
+    let mut axis = Axis::default();
+    axis.log_scale().margin(0.0);
+
+    let range = DataRange::from(Range::new(1.0, 1e6));
+    let ticks: Vec<String> = axis.iter_ticks(range.clone(), 10).map(|t| t.to_string()).collect();
+    assert_eq!(ticks, ["10^0", "10^1", "10^2", "10^3", "10^4", "10^5", "10^6"]);
+
+    axis.power_labels(PowerStyle::Prefix);
+    let ticks: Vec<String> = axis.iter_ticks(range, 10).map(|t| t.to_string()).collect();
+    assert_eq!(ticks, ["1", "10", "100", "1k", "10k", "100k", "1M"]);
+
+    let range = DataRange::from(Range::new(1.5, 60.0));
+    let ticks: Vec<String> = axis.iter_ticks(range, 10).map(|t| t.to_string()).collect();
+    assert_eq!(ticks, ["2", "5", "10", "20", "50"]);
+  }
+
+  #[test]
+  fn datetime_ticks_work() {
+    use polars::prelude::TimeUnit;
+
+    // 2026-03-07 00:00 to 2026-03-10 00:00 UTC, in milliseconds.
+    let range = Range::new(1772841600000.0, 1773100800000.0);
+    let tz = Some(chrono_tz::America::New_York);
+    let unit = RangeUnit::Datetime(TimeUnit::Milliseconds, tz);
+    let (step, ticks) = calendar::ticks(range, unit, 10);
+    assert_eq!(step, calendar::TimeStep::Hours(12));
+
+    let labels: Vec<String> =
+      ticks.iter().map(|&v| calendar::format(v, unit, Some(step), false)).collect();
+    // New York skips an hour on Mar 8, but ticks stay on local midnight and noon.
+    assert_eq!(labels, ["Mar 7", "12:00", "Mar 8", "12:00", "Mar 9", "12:00"]);
+  }
+
+  #[test]
+  fn date_ticks_work() {
+    let mut axis = Axis::default();
+    axis.margin(0.0);
+
+    // 2025-10-01 to 2026-03-31, in days since the epoch.
+    let range = DataRange::Continuous {
+      range:      Range::new(20362.0, 20543.0),
+      unit:       RangeUnit::Date,
+      margin_min: false,
+      margin_max: false,
+    };
+    let ticks: Vec<String> = axis.iter_ticks(range, 10).map(|t| t.to_string()).collect();
+    assert_eq!(ticks, ["Oct\n2025", "Nov", "Dec", "Jan\n2026", "Feb", "Mar"]);
+  }
+
+  #[test]
+  fn week_ticks_line_up() {
+    // Twenty weeks from Monday 2026-01-05, and from the Monday after, in days
+    // since the epoch.
+    let (step, a) = calendar::ticks(Range::new(20458.0, 20598.0), RangeUnit::Date, 10);
+    let (_, b) = calendar::ticks(Range::new(20465.0, 20605.0), RangeUnit::Date, 10);
+    assert_eq!(step, calendar::TimeStep::Weeks(2));
+    assert!(a.iter().chain(&b).all(|t| (t - a[0]).rem_euclid(14.0) == 0.0), "{a:?} {b:?}");
+  }
+
+  #[test]
+  fn duration_ticks_work() {
+    use polars::prelude::TimeUnit;
+
+    let ticks = calendar::duration_ticks(Range::new(0.0, 5_400_000.0), TimeUnit::Milliseconds, 10);
+    let labels: Vec<String> =
+      ticks.iter().map(|&v| calendar::format_duration(v, TimeUnit::Milliseconds)).collect();
+    assert_eq!(labels, ["0", "10m", "20m", "30m", "40m", "50m", "1h", "1h10m", "1h20m", "1h30m"]);
+
+    let ticks = calendar::duration_ticks(Range::new(-30.0, 45.0), TimeUnit::Microseconds, 10);
+    let labels: Vec<String> =
+      ticks.iter().map(|&v| calendar::format_duration(v, TimeUnit::Microseconds)).collect();
+    assert_eq!(labels, ["-30µs", "-20µs", "-10µs", "0", "10µs", "20µs", "30µs", "40µs"]);
+
+    assert_eq!(calendar::format_duration(1.5e9, TimeUnit::Nanoseconds), "1.5s");
+  }
+
+  #[test]
+  fn tick_formatters_work() {
+    assert_eq!(format::percent(0)(0.25), "25%");
+    assert_eq!(format::currency("$", 2)(-1234.5), "-$1,234.50");
+    assert_eq!(format::thousands(0)(1234567.0), "1,234,567");
+    assert_eq!(format::thousands(0)(123.0), "123");
+    assert_eq!(format::si(1)(1500.0), "1.5k");
+    assert_eq!(format::si(0)(0.002), "2m");
+    assert_eq!(format::si(0)(20e6), "20M");
+    assert_eq!(format::scientific(2)(12345.0), "1.23e4");
+    assert_eq!(format::engineering(1)(12345.0), "12.3e3");
+    assert_eq!(format::engineering(0)(0.005), "5e-3");
+
+    // Values that round up to the next prefix, or down to zero.
+    assert_eq!(format::si(2)(999.999), "1.00k");
+    assert_eq!(format::si(0)(-999_999.9), "-1M");
+    assert_eq!(format::engineering(1)(999.96), "1.0e3");
+    assert_eq!(format::currency("$", 2)(-0.001), "$0.00");
+    assert_eq!(format::thousands(0)(-0.4), "0");
+    assert_eq!(format::percent(0)(-0.001), "0%");
+
+    let mut axis = Axis::default();
+    axis.format_ticks(|v| format!("{v} m"));
+    let range = DataRange::from(Range::new(0.0, 10.0));
+    let ticks: Vec<String> = axis.iter_ticks(range, 5).map(|t| axis.tick_label(&t)).collect();
+    assert_eq!(ticks[..3], ["0 m", "2.5 m", "5 m"]);
+  }
+
+  #[test]
+  fn manual_ticks_work() {
+    let mut axis = Axis::default();
+    axis.ticks_manual([(0.0, None), (99.9, Some("SLA".to_string())), (250.0, None)]);
+
+    let range = DataRange::from(Range::new(0.0, 300.0));
+    let ticks: Vec<(f64, String)> =
+      axis.iter_ticks(range, 10).map(|t| (t.position(), axis.tick_label(&t))).collect();
+    assert_eq!(
+      ticks,
+      vec![(0.0, "0".to_string()), (99.9, "SLA".to_string()), (250.0, "250".to_string())]
+    );
+
+    // Unlabeled ticks show their exact value.
+    axis.ticks_manual([(1.5, None), (2.5, None), (99.9, None), (0.125, None)]);
+    let labels: Vec<String> =
+      axis.iter_ticks(DataRange::from(Range::new(0.0, 100.0)), 10).map(|t| t.to_string()).collect();
+    assert_eq!(labels, ["1.5", "2.5", "99.9", "0.125"]);
+  }
+
+  #[test]
+  fn crowded_tick_labels_wrap_rotate_or_thin() {
+    let mut render = Render::new();
+    let axis = Axis::default();
+    // Lays out the labels `spacing` apart along the bottom, returning the
+    // most lines any label wraps onto, the angle, and the stride they are
+    // drawn at.
+    let mut fit = |axis: &Axis, names: &[&str], spacing: f64| {
+      let labels = names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (name.to_string(), Point::new(i as f64 * spacing, 0.0)))
+        .collect::<Vec<_>>();
+      let (layouts, angle) = axis.layout_tick_labels(&mut render, Side::Bottom, &labels);
+      let lines = layouts.iter().map(|layout| layout.len()).max().unwrap();
+      (lines, angle, labels::stride(Side::Bottom, &layouts, angle, spacing))
+    };
+
+    let short = ["a", "b", "c", "d"];
+    let phrases = ["north east region", "south west region", "far north region"];
+    let words = ["supercalifragilistic", "antidisestablishment", "floccinaucinihilipil"];
+    assert_eq!(fit(&axis, &short, 100.0), (1, 0.0, 1));
+    assert_eq!(fit(&axis, &phrases, 80.0), (2, 0.0, 1));
+    assert_eq!(fit(&axis, &words, 40.0), (1, 45.0, 1));
+    assert_eq!(fit(&axis, &words, 25.0), (1, 90.0, 1));
+    let (_, angle, stride) = fit(&axis, &words, 5.0);
+    assert_eq!(angle, 90.0);
+    assert!(stride > 1, "{stride}");
+
+    let mut rotated = Axis::default();
+    rotated.tick_rotation(30.0);
+    assert_eq!(fit(&rotated, &short, 100.0).1, 30.0);
+  }
+
+  #[test]
+  fn max_ticks_thins_labels() {
+    let labels = Column::new("label".into(), (0..25).map(|i| format!("L{i}")).collect::<Vec<_>>());
+    let range = DataRange::from_column(&labels).unwrap();
+
+    let mut axis = Axis::default();
+    assert_eq!(axis.major_ticks(range.clone()).len(), 25);
+
+    axis.max_ticks(10);
+    let ticks: Vec<String> = axis.major_ticks(range).iter().map(|t| t.to_string()).collect();
+    assert_eq!(ticks, ["L0", "L3", "L6", "L9", "L12", "L15", "L18", "L21", "L24"]);
+  }
+
+  #[test]
+  fn secondary_axes_get_their_own_bounds() {
+    let x = Column::new("x".into(), [0.0, 10.0]);
+    let rate = Column::new("rate".into(), [100.0, 500.0]);
+    let latency = Column::new("latency".into(), [0.01, 0.05]);
+
+    let range = |r: DataRange| match r {
+      DataRange::Continuous { range, .. } => (range.min, range.max),
+      DataRange::Categorical(_) => panic!("expected a continuous range"),
+    };
+
+    let mut plot = Plot::new();
+    plot.line(&x, &rate);
+    plot.line(&x, &latency).on_y2();
+
+    // Without a secondary axis, everything shares the primary y axis, but
+    // the missing axis is reported.
+    assert_eq!(range(plot.bounds().y), (0.01, 500.0));
+    let err = plot.check().unwrap_err().to_string();
+    assert!(err.contains("line of `latency` against `x`") && err.contains("y2"), "{err}");
+
+    plot.y2();
+    assert!(plot.check().is_ok());
+    let bounds = plot.bounds();
+    assert_eq!(range(bounds.y), (100.0, 500.0));
+    assert_eq!(range(bounds.y2.unwrap()), (0.01, 0.05));
+    assert!(bounds.x2.is_none());
+  }
+
+  #[test]
+  fn secondary_axes_are_drawn_top_and_right() {
+    let x = Column::new("x".into(), [0.0, 10.0]);
+    let rate = Column::new("rate".into(), [100.0, 500.0]);
+    let hours = Column::new("hours".into(), [0.0, 24.0]);
+    let latency = Column::new("latency".into(), [0.01, 0.05]);
+
+    let mut plot = Plot::new();
+    plot.line(&x, &rate);
+    plot.line(&hours, &latency).on_x2().on_y2();
+    plot.x2();
+    plot.y2();
+
+    let mut render = Render::new();
+    let outer = render.bounds();
+    let bounds = plot.bounds();
+    let viewport = plot.layout(&mut render, outer, &bounds, CellLayout::default()).viewport;
+    let binding = AxisBinding { x2: true, y2: true };
+    let transform = plot.viewport_transform(binding, &bounds, viewport);
+    let (x2, y2) = (plot.x2.as_ref().unwrap(), plot.y2.as_ref().unwrap());
+    let top =
+      plot.draw_axis(&mut render, x2, Side::Top, bounds.x2.as_ref().unwrap(), &transform, viewport);
+    let right = plot.draw_axis(
+      &mut render,
+      y2,
+      Side::Right,
+      bounds.y2.as_ref().unwrap(),
+      &transform,
+      viewport,
+    );
+
+    let text = |labels: &TickLabels| labels.iter().map(|(t, _)| t.clone()).collect::<Vec<_>>();
+    assert!(text(&top).contains(&"20.0".to_string()), "{top:?}");
+    assert!(text(&right).contains(&"0.0500".to_string()), "{right:?}");
+    // Above and to the right of the viewport, which runs downwards in y.
+    assert!(top.iter().all(|(_, at)| at.y < viewport.y.max), "{top:?}");
+    assert!(right.iter().all(|(_, at)| at.x > viewport.x.max), "{right:?}");
+    assert!(plot.spine_stroke(Some(x2)).is_some() && plot.spine_stroke(Some(y2)).is_some());
+  }
+
+  #[test]
+  fn broken_axis_works() {
+    let breaks = [bounds::Break { from: 50.0, to: 900.0, gap: 10.0 }];
+    for v in [0.0, 25.0, 50.0, 900.0, 950.0] {
+      assert!((bounds::expand(&breaks, bounds::collapse(&breaks, v)) - v).abs() < 1e-9);
+    }
+    assert_eq!(bounds::collapse(&breaks, 950.0), 110.0);
+
+    let mut axis = Axis::default();
+    axis.break_range(50.0, 900.0);
+    let range = DataRange::Continuous {
+      range:      Range::new(0.0, 1000.0),
+      unit:       RangeUnit::Absolute,
+      margin_min: false,
+      margin_max: false,
+    };
+    let ticks =
+      axis.major_ticks(range.clone()).into_iter().map(|t| t.position()).collect::<Vec<_>>();
+    assert!(ticks.iter().all(|&v| !(50.0..=900.0).contains(&v) || v == 50.0 || v == 900.0));
+    assert!(ticks.iter().any(|&v| v < 50.0));
+    assert!(ticks.iter().any(|&v| v > 900.0));
+
+    // Drawn, the ticks either side of the break sit closer together than
+    // ticks the same distance apart elsewhere.
+    let x = Column::new("x".into(), [0.0, 1000.0]);
+    let mut plot = Plot::new();
+    plot.line(&x, &x);
+    plot.x.margin(0.0).break_range(50.0, 900.0);
+    let (_, _, labels) = drawn_tick_labels(&plot);
+    let text = labels.iter().map(|(text, _)| text.as_str()).collect::<Vec<_>>();
+    assert_eq!(text, ["0", "20", "40", "900", "920", "940", "960", "980", "1000"]);
+    let step = labels[1].1.x - labels[0].1.x;
+    let across = labels[3].1.x - labels[2].1.x;
+    assert!(0.0 < across && across < step, "{labels:?}");
+  }
+
+  #[test]
+  fn broken_axis_ignores_limits_past_each_segment() {
+    let mut axis = Axis::default();
+    axis.log_scale().margin(0.0).min(1e-12).break_range(1e2, 1e10);
+    let range = DataRange::from(Range::new(1.0, 1e12));
+
+    // The lower limit widens the first segment, but the decades above the
+    // break are still all ticked.
+    let labels = axis.major_ticks(range).iter().map(|t| t.to_string()).collect::<Vec<_>>();
+    assert!(labels.contains(&"10^-10".to_string()), "{labels:?}");
+    assert!(labels.ends_with(&["10^10", "10^11", "10^12"].map(String::from)), "{labels:?}");
+  }
+
+  #[test]
+  fn categories_merge_by_label() {
+    let fruit = Column::new("fruit".into(), ["apple", "pear", "plum"]);
+    let more_fruit = Column::new("fruit".into(), ["kiwi", "pear", "apple"]);
+    let sales = Column::new("sales".into(), [3, 5, 2]);
+    let target = Column::new("target".into(), [4.0, 4.0, 4.0]);
+
+    let mut plot = Plot::new();
+    plot.bar_chart(&fruit, &sales);
+    plot.bar_chart(&more_fruit, &sales);
+    plot.line(&fruit, &target);
+    assert_eq!(plot.bounds().x.categories(), ["apple", "pear", "plum", "kiwi"]);
+
+    plot.x.categories(["plum", "kiwi"]);
+    let bounds = plot.bounds();
+    assert_eq!(bounds.x.categories(), ["plum", "kiwi", "apple", "pear"]);
+
+    let transform = plot.viewport_transform(
+      AxisBinding::default(),
+      &bounds,
+      Bounds::new(Range::new(0.0, 1.0), Range::new(0.0, 1.0)),
+    );
+    let p = transform.data_point("apple".into(), AnyValue::Float64(4.0)).unwrap();
+    assert_eq!(p, Point::new(2.0, 4.0));
+
+    // Listed categories keep their slot even without data, and repeated
+    // labels share one.
+    let repeated = Column::new("fruit".into(), ["pear", "fig", "pear"]);
+    let mut plot = Plot::new();
+    plot.bar_chart(&repeated, &sales);
+    plot.x.categories(["kiwi"]);
+    assert_eq!(plot.bounds().x.categories(), ["kiwi", "pear", "fig"]);
+  }
+
+  #[test]
+  fn barh_puts_categories_on_y() {
+    let names = Column::new("name".into(), ["a", "b"]);
+    let counts = Column::new("count".into(), [3, 7]);
+
+    let mut plot = Plot::new();
+    plot.barh(&names, &counts);
+    let bounds = plot.bounds();
+    assert_eq!(bounds.y.categories(), ["a", "b"]);
+    assert!(matches!(bounds.x, DataRange::Continuous { range, .. } if range.max == 7.0));
+
+    // The names are drawn left of the viewport, from the bottom up.
+    let (viewport, left, bottom) = drawn_tick_labels(&plot);
+    let (a, b) = (&left[0], &left[1]);
+    assert_eq!((a.0.as_str(), b.0.as_str()), ("a", "b"));
+    assert!(a.1.x < viewport.x.min && a.1.x == b.1.x);
+    assert!(a.1.y > b.1.y);
+    assert_eq!(bottom.last().map(|(text, _)| text.as_str()), Some("7.00"));
+  }
+
+  #[test]
+  fn spines_can_sit_at_zero() {
+    let transform = ViewportTransform {
+      affine:       Bounds::new(Range::new(-1.0, 1.0), Range::new(-2.0, 2.0))
+        .transform_to(Bounds::new(Range::new(0.0, 100.0), Range::new(100.0, 0.0))),
+      x:            Scale::Linear,
+      y:            Scale::Linear,
+      x_breaks:     vec![],
+      y_breaks:     vec![],
+      x_categories: HashMap::new(),
+      y_categories: HashMap::new(),
+      x_unit:       RangeUnit::Absolute,
+      y_unit:       RangeUnit::Absolute,
+    };
+    let viewport = Bounds::new(Range::new(0.0, 100.0), Range::new(100.0, 0.0));
+
+    let mut axis = Axis::default();
+    assert_eq!(axis.spine_bounds(Side::Left, &transform, viewport).x.min, 0.0);
+
+    axis.spine().at_zero();
+    assert_eq!(axis.spine_bounds(Side::Left, &transform, viewport).x.min, 50.0);
+    assert_eq!(axis.spine_bounds(Side::Bottom, &transform, viewport).y.min, 50.0);
+
+    // Values off the plot put the line on the nearest edge of the viewport.
+    axis.spine().position(SpinePosition::Value(5.0));
+    assert_eq!(axis.spine_bounds(Side::Left, &transform, viewport).x.min, 100.0);
+    axis.spine().position(SpinePosition::Value(-5.0));
+    assert_eq!(axis.spine_bounds(Side::Left, &transform, viewport).x.min, 0.0);
+
+    // Drawn, the tick labels follow the lines across the plot.
+    let x = Column::new("x".into(), [-1.0, 1.0]);
+    let y = Column::new("y".into(), [-2.0, 2.0]);
+    let mut plot = Plot::new();
+    plot.line(&x, &y);
+    plot.x.spine().at_zero();
+    plot.y.spine().at_zero();
+    let (viewport, left, bottom) = drawn_tick_labels(&plot);
+    let offset = plot.y.spine.label_offset();
+    assert!(left.iter().all(|(_, at)| at.x == viewport.x.center() - offset), "{left:?}");
+    assert!(bottom.iter().all(|(_, at)| at.y == viewport.y.center() + offset), "{bottom:?}");
+
+    plot.y.spine().position(SpinePosition::Value(5.0));
+    let (viewport, left, _) = drawn_tick_labels(&plot);
+    assert!(left.iter().all(|(_, at)| at.x == viewport.x.max - offset), "{left:?}");
+  }
+
+  #[test]
+  fn styled_spines_outlive_the_border() {
+    let mut plot = Plot::new();
+    plot.no_border();
+    assert!(plot.spine_stroke(Some(&plot.x)).is_none());
+
+    plot.x.spine().stroke();
+    assert!(plot.spine_stroke(Some(&plot.x)).is_some());
+    assert!(plot.spine_stroke(Some(&plot.y)).is_none());
+    assert!(plot.spine_stroke(None).is_none());
+
+    plot.spines(Spines::None);
+    assert!(plot.spine_stroke(Some(&plot.x)).is_none());
+  }
+
+  #[test]
+  fn equal_aspect_letterboxes() {
+    let x = Column::new("x".into(), [0.0, 2.0]);
+    let y = Column::new("y".into(), [0.0, 1.0]);
+
+    let mut plot = Plot::new();
+    plot.x.margin(0.0);
+    plot.y.margin(0.0);
+    plot.line(&x, &y);
+    plot.equal_aspect();
+
+    let viewport =
+      plot.letterbox(Bounds::new(Range::new(0.0, 100.0), Range::new(100.0, 0.0)), &plot.bounds());
+    assert_eq!((viewport.x.min, viewport.x.max), (0.0, 100.0));
+    assert_eq!((viewport.y.min, viewport.y.max), (75.0, 25.0));
+
+    // Drawn, one unit spans as far along x as along y.
+    let (viewport, left, bottom) = drawn_tick_labels(&plot);
+    let along = |labels: &[(String, Point)], text: &str| {
+      labels.iter().find(|(t, _)| t == text).map(|(_, at)| *at).unwrap()
+    };
+    let x_unit = along(&bottom, "1.00").x - along(&bottom, "0.00").x;
+    let y_unit = along(&left, "0.00").y - along(&left, "1.00").y;
+    assert!((x_unit - y_unit).abs() < 1e-9, "{x_unit} {y_unit}");
+    assert!((viewport.x.size() + 2.0 * viewport.y.size()).abs() < 1e-9, "{viewport:?}");
+  }
+
+  #[test]
+  fn mixed_units_convert_or_fail() {
+    let days = Column::new("day".into(), [0, 1]).cast(&DataType::Date).unwrap();
+    let times = Column::new("time".into(), [43_200_000_i64, 259_200_000])
+      .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))
+      .unwrap();
+    let waits = Column::new("wait".into(), [1_i64, 2])
+      .cast(&DataType::Duration(TimeUnit::Milliseconds))
+      .unwrap();
+    let y = Column::new("y".into(), [1.0, 2.0]);
+
+    let mut plot = Plot::new();
+    plot.line(&days, &y);
+    plot.scatter(&times, &y);
+    assert!(plot.check().is_ok());
+    let DataRange::Continuous {
+      range, unit: RangeUnit::Datetime(TimeUnit::Milliseconds, _), ..
+    } = plot.bounds().x
+    else {
+      panic!("expected a datetime range");
+    };
+    assert_eq!((range.min, range.max), (0.0, 259_200_000.0));
+
+    let fruit = Column::new("fruit".into(), ["apple", "pear"]);
+    let mut categories = Plot::new();
+    categories.bar_chart(&fruit, &y);
+    categories.line(&days, &y);
+    let err = categories.check().unwrap_err().to_string();
+    assert!(err.contains("dates") && err.contains("categories"), "{err}");
+
+    plot.line(&waits, &y);
+    let err = plot.check().unwrap_err().to_string();
+    assert!(err.contains("line of `y` against `wait`"), "{err}");
+    assert!(err.contains("durations") && err.contains("datetimes"), "{err}");
+
+    let path = std::env::temp_dir().join("neilplot-mixed-units.png");
+    let err = plot.save(&path).unwrap_err().to_string();
+    assert!(err.contains("line of `y` against `wait`"), "{err}");
+    assert!(!path.exists());
+
+    let mut figure = Figure::new(1, 2);
+    figure.share_x();
+    figure.plot(0, 0).line(&days, &y);
+    figure.plot(0, 1).line(&waits, &y);
+    let err = figure.check().unwrap_err().to_string();
+    assert!(err.contains("shared x axes"), "{err}");
+  }
+
+  #[test]
+  fn figure_cells_span_and_space() {
+    let mut figure = Figure::new(2, 2);
+    figure.spacing(10.0);
+    let outer = Bounds::new(Range::new(0.0, 210.0), Range::new(110.0, 0.0));
+
+    let cell = figure.cell_bounds(outer, &(0..1), &(1..2));
+    assert_eq!((cell.x.min, cell.x.max), (110.0, 210.0));
+    assert_eq!((cell.y.min, cell.y.max), (50.0, 0.0));
+
+    let wide = figure.cell_bounds(outer, &(1..2), &(0..2));
+    assert_eq!((wide.x.min, wide.x.max), (0.0, 210.0));
+    assert_eq!((wide.y.min, wide.y.max), (110.0, 60.0));
+
+    // A suptitle pushes the grid down by its measured height.
+    let mut render = Render::new();
+    assert_eq!(figure.grid_bounds(&mut render).y.max, render.bounds().y.max);
+    figure.title("Results");
+    let below = figure.grid_bounds(&mut render).y.max;
+    let title = "Results".to_string();
+    let height = title_height(&mut render, Some(&title), 40.0, FontWeight::BOLD);
+    assert_eq!(below, render.bounds().y.max + 15.0 + height);
+  }
+
+  #[test]
+  #[should_panic(expected = "overlaps the plot at 1..2, 0..1")]
+  fn figure_rejects_overlapping_spans() {
+    let mut figure = Figure::new(2, 2);
+    figure.plot(1, 0);
+    figure.plot_span(0..2, 0..1);
+  }
+
+  #[test]
+  fn figure_shares_axes() {
+    let a = Column::new("a".into(), [0.0, 1.0]);
+    let b = Column::new("b".into(), [5.0, 10.0]);
+    let mut figure = Figure::new(2, 2);
+    figure.share_x();
+    figure.plot(0, 0).line(&a, &a);
+    figure.plot(1, 0).line(&b, &b);
+    figure.plot(1, 1).line(&a, &b);
+
+    let range = |r: &DataRange| match r {
+      DataRange::Continuous { range, .. } => (range.min, range.max),
+      DataRange::Categorical(_) => panic!("expected a continuous range"),
+    };
+    let bounds = figure.plot_bounds();
+    assert!(bounds.iter().all(|b| range(&b.x) == (0.0, 10.0)));
+    assert_eq!(range(&bounds[0].y), (0.0, 1.0));
+
+    let hidden = figure.cells.iter().map(|cell| figure.hidden_labels(cell)).collect::<Vec<_>>();
+    assert_eq!(
+      hidden.iter().map(|h| (h.x, h.y)).collect::<Vec<_>>(),
+      [(true, false), (false, false), (false, false)]
+    );
+  }
+
+  #[test]
+  fn layout_fits_tick_labels() {
+    let short = Column::new("short".into(), [1.0, 2.0]);
+    let long = Column::new("long".into(), [100000.0, 200000.0]);
+    let mut render = Render::new();
+    let outer = render.bounds();
+    let mut left = |plot: &Plot| {
+      plot.layout(&mut render, outer, &plot.bounds(), CellLayout::default()).viewport.x.min
+    };
+
+    let mut narrow = Plot::new();
+    narrow.line(&short, &short);
+    let mut wide = Plot::new();
+    wide.line(&short, &long);
+    assert!(left(&wide) > left(&narrow));
+
+    wide.margins().left(150.0);
+    assert_eq!(left(&wide), 150.0);
+
+    // An outside legend doesn't overlap the viewport when the margin on its
+    // side is set.
+    wide.margins().right(40.0).bottom(40.0);
+    let layout = wide.layout(&mut render, outer, &wide.bounds(), CellLayout::default());
+    assert_eq!(layout.viewport.x.max, outer.x.max - 40.0);
+    wide.legend(LegendPosition::OutsideRight);
+    let (room, _) = wide.legend_room(&mut render);
+    let layout = wide.layout(&mut render, outer, &wide.bounds(), CellLayout::default());
+    assert_eq!(layout.viewport.x.max, outer.x.max - 40.0 - room);
+    wide.legend(LegendPosition::Below);
+    let (_, room) = wide.legend_room(&mut render);
+    let layout = wide.layout(&mut render, outer, &wide.bounds(), CellLayout::default());
+    assert_eq!(layout.viewport.y.min, outer.y.min - 40.0 - room);
+  }
+
+  #[test]
+  fn legend_avoids_data() {
+    // Data crowded into the top right corner.
+    let x = Column::new("x".into(), [0.0, 0.9, 0.95, 1.0]);
+    let y = Column::new("y".into(), [0.0, 0.9, 0.95, 1.0]);
+    let mut render = Render::new();
+    let outer = render.bounds();
+
+    let mut plot = Plot::new();
+    plot.scatter(&x, &y);
+    plot.legend(LegendPosition::Best);
+    let bounds = plot.bounds();
+    let viewport = plot.layout(&mut render, outer, &bounds, CellLayout::default()).viewport;
+    let size = kurbo::Size::new(200.0, 100.0);
+    assert_eq!(plot.best_legend_corner(size, viewport, &bounds), LegendPosition::TopLeft);
+
+    // Legends inside the viewport take no extra room, outside ones do.
+    assert_eq!(plot.legend_room(&mut render), (0.0, 0.0));
+    plot.legend(LegendPosition::OutsideRight);
+    assert!(plot.legend_room(&mut render).0 > 0.0);
+  }
+
+  #[test]
+  fn trendlines_follow_converted_points() {
+    let days = Column::new("day".into(), [0, 1, 2]).cast(&DataType::Date).unwrap();
+    let times = Column::new("time".into(), [0_i64])
+      .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))
+      .unwrap();
+    let y = Column::new("y".into(), [1.0, 2.0, 3.0]);
+    let first = y.slice(0, 1);
+
+    // The dates are drawn in milliseconds, to share the axis with the
+    // datetimes, and the trendline is fitted to the same positions.
+    let mut plot = Plot::new();
+    plot.scatter(&days, &y).trendline(TrendlineKind::LINEAR);
+    plot.scatter(&times, &first);
+    let bounds = plot.bounds();
+    let viewport = Bounds::new(Range::new(0.0, 100.0), Range::new(100.0, 0.0));
+    let transform = plot.viewport_transform(AxisBinding::default(), &bounds, viewport);
+    let Axes::Scatter(scatter) = &plot.axes[0] else { panic!("expected a scatter") };
+    let line = scatter.trendline_line(&transform).unwrap();
+    assert_eq!(line.p0, Point::new(0.0, 1.0));
+    assert!((line.p1.x - 172_800_000.0).abs() < 1e-6 && (line.p1.y - 3.0).abs() < 1e-9);
+  }
+
+  #[test]
+  fn series_labels() {
+    let x = Column::new("x".into(), [1.0, 2.0]);
+    let y = Column::new("y".into(), [1.0, 2.0]);
+    let mut plot = Plot::new();
+    assert_eq!(plot.line(&x, &y).legend_label(), "y");
+    assert_eq!(plot.scatter(&x, &y).label("points").legend_label(), "points");
+    assert_eq!(plot.histogram(&y, 2).legend_label(), "y");
+
+    // Every kind of series gets a legend entry, not just scatters.
+    let mut render = Render::new();
+    let mut plot = Plot::new();
+    plot.histogram(&y, 2);
+    plot.legend(LegendPosition::OutsideRight);
+    assert!(plot.legend_room(&mut render).0 > 0.0);
+
+    // A scatter with a trendline gets one entry, with both its marker and
+    // the line.
+    let mut plot = Plot::new();
+    plot.scatter(&x, &y).trendline(TrendlineKind::LINEAR);
+    let items = plot.legend_items();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].label, "y");
+    assert!(items[0].marker.is_some() && items[0].line.is_some());
+  }
 }
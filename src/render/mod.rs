@@ -3,12 +3,13 @@ use std::path::Path;
 use kurbo::{Affine, Point, Rect, Shape, Size, Stroke};
 use parley::{Alignment, FontWeight, Layout, PositionedLayoutItem, StyleProperty};
 use peniko::{Brush, BrushRef, Color, Fill};
+use polars::prelude::PolarsResult;
 use vello::{
   Renderer,
   wgpu::{self, TextureDescriptor},
 };
 
-use crate::Plot;
+use crate::{Bounds, CellLayout, Figure, Plot, Range};
 
 mod texture;
 mod window;
@@ -45,6 +46,8 @@ pub struct DrawText<'a> {
   pub transform:        Affine,
   pub horizontal_align: Align,
   pub vertical_align:   Align,
+  /// Wraps the text onto multiple lines no wider than this.
+  pub max_width:        Option<f32>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -65,49 +68,106 @@ impl Default for DrawText<'_> {
       transform:        Affine::IDENTITY,
       horizontal_align: Align::Start,
       vertical_align:   Align::Start,
+      max_width:        None,
+    }
+  }
+}
+
+/// Something that fills a whole render surface, such as a single [`Plot`] or a
+/// [`Figure`] of several.
+pub(crate) trait Draw {
+  /// Draws a whole frame, with a crosshair at `cursor` (in logical
+  /// coordinates) if there is one.
+  fn draw(&self, render: &mut Render, cursor: Option<Point>);
+
+  /// The size of the image written by `save`, in pixels.
+  fn image_size(&self) -> (u32, u32) { (2048, 2048) }
+}
+
+impl Draw for Plot<'_> {
+  fn draw(&self, render: &mut Render, cursor: Option<Point>) {
+    let outer = render.bounds();
+    let bounds = self.bounds();
+    let layout = self.layout(render, outer, &bounds, CellLayout::default());
+    self.draw_in(render, outer, &bounds, &layout);
+    if let Some(cursor) = cursor {
+      self.draw_cursor_in(render, outer, &bounds, &layout, cursor);
     }
   }
 }
 
 impl Plot<'_> {
-  pub fn save(&self, path: impl AsRef<Path>) {
-    let config = RenderConfig { width: 2048, height: 2048 };
-    let handle = GpuHandle::new(&config);
-    self.render(&handle, config);
-    texture::save(handle, config, path.as_ref());
+  /// Draws the plot to an image file. Fails without drawing anything if the
+  /// series can't share their axes, as found by [`Plot::check`].
+  pub fn save(&self, path: impl AsRef<Path>) -> PolarsResult<()> {
+    self.check()?;
+    save(self, path.as_ref());
+    Ok(())
   }
 
-  pub fn show(&self) { window::show(self); }
-
-  fn render(&self, handle: &GpuHandle, config: RenderConfig) {
-    let mut render = Render::new();
-    render.resize(config);
-    self.draw(&mut render);
-
-    let view = &handle.texture.create_view(&wgpu::TextureViewDescriptor::default());
-
-    let mut renderer = Renderer::new(&handle.device, vello::RendererOptions::default())
-      .expect("Failed to create renderer");
-
-    renderer
-      .render_to_texture(
-        &handle.device,
-        &handle.queue,
-        &render.scene,
-        &view,
-        &vello::RenderParams {
-          base_color:          render.background,
-          width:               config.width,
-          height:              config.height,
-          antialiasing_method: vello::AaConfig::Msaa16,
-        },
-      )
-      .expect("Failed to render to a texture");
+  /// Opens the plot in a window. Fails without opening it if the series
+  /// can't share their axes, as found by [`Plot::check`].
+  pub fn show(&self) -> PolarsResult<()> {
+    self.check()?;
+    window::show(self);
+    Ok(())
+  }
+}
+
+impl Figure<'_> {
+  /// Draws the figure to an image file. Fails without drawing anything if
+  /// [`Figure::check`] does.
+  pub fn save(&self, path: impl AsRef<Path>) -> PolarsResult<()> {
+    self.check()?;
+    save(self, path.as_ref());
+    Ok(())
+  }
+
+  /// Opens the figure in a window. Fails without opening it if
+  /// [`Figure::check`] does.
+  pub fn show(&self) -> PolarsResult<()> {
+    self.check()?;
+    window::show(self);
+    Ok(())
   }
 }
 
+fn save(drawing: &dyn Draw, path: &Path) {
+  let (width, height) = drawing.image_size();
+  let config = RenderConfig { width, height };
+  let handle = GpuHandle::new(&config);
+  render(drawing, &handle, config);
+  texture::save(handle, config, path);
+}
+
+fn render(drawing: &dyn Draw, handle: &GpuHandle, config: RenderConfig) {
+  let mut render = Render::new();
+  render.resize(config);
+  drawing.draw(&mut render, None);
+
+  let view = &handle.texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+  let mut renderer = Renderer::new(&handle.device, vello::RendererOptions::default())
+    .expect("Failed to create renderer");
+
+  renderer
+    .render_to_texture(
+      &handle.device,
+      &handle.queue,
+      &render.scene,
+      &view,
+      &vello::RenderParams {
+        base_color:          render.background,
+        width:               config.width,
+        height:              config.height,
+        antialiasing_method: vello::AaConfig::Msaa16,
+      },
+    )
+    .expect("Failed to render to a texture");
+}
+
 impl Render {
-  fn new() -> Self {
+  pub(crate) fn new() -> Self {
     Render {
       scene:      vello::Scene::new(),
       font:       parley::FontContext::new(),
@@ -126,6 +186,11 @@ impl Render {
     }
   }
 
+  /// The whole surface, in logical coordinates, with `y` increasing downwards.
+  pub fn bounds(&self) -> Bounds {
+    Bounds::new(Range::new(0.0, self.size().width), Range::new(self.size().height, 0.0))
+  }
+
   pub fn stroke<'a>(
     &mut self,
     shape: &impl Shape,
@@ -159,8 +224,13 @@ impl Render {
 
     let mut layout = builder.build(text.text);
 
-    layout.break_all_lines(None);
-    layout.align(None, Alignment::Start, Default::default());
+    layout.break_all_lines(text.max_width);
+    let alignment = match text.horizontal_align {
+      Align::Start => Alignment::Start,
+      Align::Center => Alignment::Center,
+      Align::End => Alignment::End,
+    };
+    layout.align(None, alignment, Default::default());
 
     layout
   }
@@ -1,18 +1,18 @@
+use std::sync::Arc;
+
+use kurbo::Point;
 use vello::{
   util::{DeviceHandle, RenderContext, RenderSurface},
   wgpu,
 };
 
-use crate::{
-  Plot,
-  render::{Render, RenderConfig},
-};
+use crate::render::{Draw, Render, RenderConfig};
 
-pub fn show(plot: &Plot) {
+pub(crate) fn show(plot: &dyn Draw) {
   let event_loop = winit::event_loop::EventLoop::new().unwrap();
   event_loop.set_control_flow(winit::event_loop::ControlFlow::Wait);
 
-  let mut app = App { plot, stale: true, render: None, init: None };
+  let mut app = App { plot, stale: true, cursor: None, render: None, init: None };
   event_loop.run_app(&mut app).unwrap();
 
   // FIXME: Ideally, we'd drop this. But dropping it segfaults.
@@ -20,14 +20,16 @@ pub fn show(plot: &Plot) {
 }
 
 struct App<'a> {
-  plot:   &'a Plot<'a>,
+  plot:   &'a dyn Draw,
   stale:  bool,
+  cursor: Option<Point>,
   render: Option<Render>,
 
   init: Option<Init>,
 }
 
 struct Init {
+  window:  Arc<winit::window::Window>,
   cx:      RenderContext,
   surface: RenderSurface<'static>,
   dev_id:  usize,
@@ -41,17 +43,19 @@ impl winit::application::ApplicationHandler for App<'_> {
       return;
     }
 
-    let window = event_loop
-      .create_window(
-        winit::window::Window::default_attributes()
-          .with_min_inner_size(winit::dpi::LogicalSize::new(100, 100)),
-      )
-      .unwrap();
+    let window = Arc::new(
+      event_loop
+        .create_window(
+          winit::window::Window::default_attributes()
+            .with_min_inner_size(winit::dpi::LogicalSize::new(100, 100)),
+        )
+        .unwrap(),
+    );
     let size = window.inner_size();
 
     let mut cx = RenderContext::new();
     let surface = pollster::block_on(cx.create_surface(
-      window,
+      window.clone(),
       size.width,
       size.height,
       wgpu::PresentMode::AutoNoVsync,
@@ -63,7 +67,7 @@ impl winit::application::ApplicationHandler for App<'_> {
     let vello = vello::Renderer::new(&cx.devices[dev_id].device, vello::RendererOptions::default())
       .expect("Failed to create renderer");
 
-    self.init = Some(Init { cx, surface, dev_id, vello });
+    self.init = Some(Init { window, cx, surface, dev_id, vello });
   }
 
   fn window_event(
@@ -94,6 +98,22 @@ impl winit::application::ApplicationHandler for App<'_> {
         }
       }
 
+      winit::event::WindowEvent::CursorMoved { position, .. } => {
+        self.cursor = Some(Point::new(position.x, position.y));
+        self.stale = true;
+        if let Some(init) = &self.init {
+          init.window.request_redraw();
+        }
+      }
+
+      winit::event::WindowEvent::CursorLeft { .. } => {
+        self.cursor = None;
+        self.stale = true;
+        if let Some(init) = &self.init {
+          init.window.request_redraw();
+        }
+      }
+
       winit::event::WindowEvent::RedrawRequested => {
         if let Some(init) = &mut self.init {
           if self.render.is_none() || self.stale {
@@ -106,7 +126,10 @@ impl winit::application::ApplicationHandler for App<'_> {
               width:  init.surface.config.width,
               height: init.surface.config.height,
             });
-            self.plot.draw(self.render.as_mut().unwrap());
+            let render = self.render.as_mut().unwrap();
+            // The cursor is in physical pixels, so undo the render scale.
+            let cursor = self.cursor.map(|cursor| render.transform.inverse() * cursor);
+            self.plot.draw(render, cursor);
 
             let handle = &init.cx.devices[init.dev_id];
             init